@@ -0,0 +1,19 @@
+
+// Distinguishes a command that could not be sent 
+// from one that was sent but received no matching report.
+#[derive(Debug)]
+pub(crate) enum RequestError {
+    Send(anyhow::Error),
+    Response(anyhow::Error),
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::Send(e) => write!(f, "Command was not sent: {}", e),
+            RequestError::Response(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
@@ -1,7 +1,9 @@
 
+use std::time::Duration;
+
 use animusd_lib::protocol::{ Command, Action, Report, Outcome };
 
-use crate::file::{ animi::*, remote::* };
+use crate::animus::RequestError;
 
 impl crate::Brainstorm {
 
@@ -12,20 +14,14 @@ impl crate::Brainstorm {
             return Err(anyhow::anyhow!("'{}' is an invalid name.", animus))
         }
 
-        self.send_command(animus, Action::Query)?;
-
-        let mut buf = [0; 1023];
         // If no response (timeout), interpret as false
-        if let Ok((len, _)) = self.socket.recv_from(&mut buf) {
-            let report = Report::decode(&buf[..len])?;
-
-
-            match report.outcome {
+        match self.request(animus, Action::Query) {
+            Ok(report) => match report.outcome {
                 Outcome::Success => Ok(true),
                 _ => Ok(false),
-            }
-        } else {
-            Ok(false)
+            },
+            Err(RequestError::Send(e)) => Err(e),
+            Err(RequestError::Response(..)) => Ok(false),
         }
     }
 
@@ -36,11 +32,7 @@ impl crate::Brainstorm {
             return Err(anyhow::anyhow!("'{}' is an invalid name.", animus))
         }
 
-        self.send_command(animus, Action::Status)?;
-
-        let mut buf = [0; 1023];
-        let (len, _) = self.socket.recv_from(&mut buf)?;
-        let report = Report::decode(&buf[..len])?;
+        let report = self.request(animus, Action::Status)?;
 
         match report.outcome {
             Outcome::Success => Ok(true),
@@ -48,40 +40,107 @@ impl crate::Brainstorm {
         }
     }
 
-    // Send command to local or remote animus
-    pub(crate) fn send_command(&self, animus: &str, action: Action) -> anyhow::Result<()> {
+    // Send a command and wait for its report.
+    // Idempotent actions are resent if no report arrives, with increasing delay.
+    pub(crate) fn request(&self, animus: &str, action: Action) -> Result<Report, RequestError> {
+
+        let attempts = if is_idempotent(&action) { self.config.retries + 1 } else { 1 };
+        let timeout = match action {
+            Action::Save => self.config.save_timeout,
+            _ => self.config.timeout,
+        };
 
-        if local_animus_exists(animus)? {
-            self.send_local_command(animus, action)
-        } else if remote_animus_exists(animus)? {
-            self.send_remote_command(animus, action)
-        } else {
-            Err(anyhow::anyhow!("Animus '{}' does not exist", animus))
+        let mut backoff = self.config.backoff;
+        let mut last_error = None;
+
+        for attempt in 0..attempts {
+
+            if attempt > 0 {
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+
+            self.send_command(animus, action.clone())
+                .map_err(RequestError::Send)?;
+
+            match self.read_report_within(animus, &action, timeout) {
+                Ok(report) => return Ok(report),
+                Err(e) => last_error = Some(e),
+            }
         }
+
+        Err(RequestError::Response(anyhow::anyhow!(
+            "No response from '{}' to '{}' after {} attempt(s): {}", 
+            animus, action, attempts, 
+            last_error.expect("At least one attempt is made")
+        )))
     }
 
-    // Returns an error if the network connection could not be established.
-    pub(crate) fn send_local_command(&self, animus: &str, action: Action) -> anyhow::Result<()> {
+    // Send a command whose only expected outcome is success,
+    // treating any other outcome as an error.
+    pub(crate) fn request_success(&self, animus: &str, action: Action) -> anyhow::Result<()> {
 
-        let command = Command::new(animus, action);
-        self.socket.send(&command.encode()?)?;
+        let report = self.request(animus, action.clone())?;
 
-        Ok(())
+        match report.outcome {
+            Outcome::Success => Ok(()),
+            other => Err(anyhow::anyhow!(
+                "Command '{}' to '{}' failed: {}", 
+                action, animus, crate::output::outcome_text(other)
+            )),
+        }
     }
 
-    // Send command to associated IP address @ port 4048.
-    pub(crate) fn send_remote_command(&self, animus: &str, action: Action) -> anyhow::Result<()> {
+    // Wait for a report using a timeout other than the default.
+    fn read_report_within(
+        &self, 
+        animus: &str, 
+        action: &Action, 
+        timeout: Duration
+    ) -> anyhow::Result<Report> {
 
-        let ip_addr = remote_animus_ip(animus)?;
-        let remote = std::net::SocketAddr::new(ip_addr, 4048);
+        self.socket.set_read_timeout(Some(timeout))?;
+        let report = self.read_report(animus, action);
+        self.socket.set_read_timeout(Some(self.config.timeout))?;
 
-        let command = Command::new(animus, action);
-        self.socket.send_to(&command.encode()?, remote)?;
+        report
+    }
+
+    // Send an action directly to a known address and wait for the report.
+    pub(crate) fn request_at(
+        &self,
+        animus: &str,
+        addr: std::net::SocketAddr,
+        action: Action,
+        timeout: Duration,
+    ) -> anyhow::Result<Report> {
+
+        let command = Command::new(animus, action.clone());
+        self.socket.send_to(&command.encode()?, self.destination(addr))?;
+
+        self.read_report_within(animus, &action, timeout)
+    }
+
+    // Send command to wherever the animus is routed.
+    pub(crate) fn send_command(&self, animus: &str, action: Action) -> anyhow::Result<()> {
+
+        let addr = self.route(animus)?;
 
         // animus recieves using recv_from
         // animus reports to this socket's IP
+        let command = Command::new(animus, action);
+        self.socket.send_to(&command.encode()?, addr)?;
 
         Ok(())
     }
 
 }
+
+// Actions that can safely be sent again if the first report is lost.
+fn is_idempotent(action: &Action) -> bool {
+    matches!(action, 
+        Action::Query | Action::Status | Action::Version | Action::Name 
+        | Action::ListStructures | Action::ListInputs | Action::ListOutputs 
+        | Action::ReportInputs | Action::InputInfo(..)
+    )
+}
@@ -1,4 +1,7 @@
 
 mod command;
 mod report;
+mod route;
+mod save;
 
+pub(crate) use report::MAX_DATAGRAM;
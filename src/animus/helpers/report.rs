@@ -1,23 +1,70 @@
 
-use animusd_lib::protocol::Report;
+use std::time::Instant;
+
+use animusd_lib::protocol::{ Action, Outcome, Report };
+
+// Largest payload a single UDP datagram can carry.
+// Reports are not fragmented, so a report must encode to at most this many bytes.
+// (Splitting larger reports needs support from animusd-lib on the sending side.)
+pub(crate) const MAX_DATAGRAM: usize = 65_507;
 
 impl crate::Brainstorm {
 
-    pub(crate) fn share_response(&self) -> anyhow::Result<()> {
-        Ok(Self::print_report(self.read_report()?))
-    }
-    
-    pub(crate) fn read_report(&self) -> anyhow::Result<Report> {
+    // Wait for the report from this animus for this action.
+    // Reports that do not match are stale replies to earlier commands, 
+    // so they are discarded with a warning instead of being misattributed.
+    pub(crate) fn read_report(&self, animus: &str, action: &Action) -> anyhow::Result<Report> {
+
+        // The socket timeout applies to the whole wait, not to each datagram
+        let timeout = self.socket.read_timeout()?;
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        let mut buf = vec![0; MAX_DATAGRAM];
+
+        let result = loop {
 
-        let mut buf = [0; 1023];
-        let (len, _) = self.socket.recv_from(&mut buf)?;
-        let report = Report::decode(&buf[..len])?;
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break Err(timeout_error(animus, action))
+                }
+                self.socket.set_read_timeout(Some(remaining))?;
+            }
 
-        Ok(report)
+            let len = match self.socket.recv_from(&mut buf) {
+                Ok((len, _)) => len,
+                Err(e) => break Err(e.into()),
+            };
+
+            let report = match Report::decode(&buf[..len]) {
+                Ok(report) => report,
+                Err(e) => {
+                    eprintln!("WARN: Discarding a report that could not be decoded: {}", e);
+                    continue
+                }
+            };
+
+            if report_matches(&report, animus, action) { break Ok(report) }
+
+            eprintln!(
+                "WARN: Discarding unmatched report from '{}' ({})", 
+                report.name, report.action
+            );
+        };
+
+        self.socket.set_read_timeout(timeout)?;
+        result
     }
 
+    // A failed outcome marks the command as failed, for the exit code.
     pub(crate) fn print_report(report: Report) {
 
+        if report_failed(&report) { crate::report_failure() }
+
+        if crate::output::is_json() {
+            return crate::output::emit(crate::output::report_json(&report))
+        }
+
         println!{
             "{} ({}): {}",
             report.name,
@@ -27,3 +74,111 @@ impl crate::Brainstorm {
     }
 }
 
+// Explain a missing report. A list too large for one datagram is never delivered,
+// which looks the same as no answer at all.
+fn timeout_error(animus: &str, action: &Action) -> anyhow::Error {
+    match action {
+        Action::ListStructures | Action::ListInputs | Action::ListOutputs | Action::ReportInputs => {
+            anyhow::anyhow!(
+                "Timed out waiting for a report from '{}'. \
+                The report may have been too large to send: \
+                reports are limited to {} bytes in a single datagram.",
+                animus, MAX_DATAGRAM
+            )
+        },
+        _ => anyhow::anyhow!("Timed out waiting for a report from '{}'", animus),
+    }
+}
+
+// Reports are matched on animus name and the kind of action.
+fn report_matches(report: &Report, animus: &str, action: &Action) -> bool {
+    report.name == animus 
+        && std::mem::discriminant(&report.action) == std::mem::discriminant(action)
+}
+
+// Whether the animus could not carry out the action.
+// A Status report that isn't Success only means the animus is asleep.
+fn report_failed(report: &Report) -> bool {
+    !matches!(report.outcome, Outcome::Success | Outcome::Return(..))
+        && !matches!(report.action, Action::Status)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::net::{ SocketAddr, UdpSocket };
+
+    use animusd_lib::protocol::{ Action, Outcome, Report };
+    use cajal_cx::tract::receiver::ReceiverInfo;
+
+    use super::MAX_DATAGRAM;
+
+    // A Brainstorm bound to an ephemeral loopback port.
+    fn brainstorm() -> crate::Brainstorm {
+        let config = crate::file::config::BrainstormConfig {
+            port: Some(0),
+            ..Default::default()
+        };
+        crate::Brainstorm::new(config).expect("Bind to loopback")
+    }
+
+    // A ReportInputs report with thousands of entries, as a large complex would send.
+    fn large_inputs_report(animus: &str) -> (Report, Vec<ReceiverInfo>) {
+
+        let address: SocketAddr = "127.0.0.1:40000".parse().unwrap();
+        let inputs: Vec<ReceiverInfo> = (0..2000)
+            .map(|i| ReceiverInfo { tract_name: format!("input_{:04}", i), address })
+            .collect();
+
+        let msg = bincode::serialize(&inputs).unwrap();
+        let report = Report::new(animus, Action::ReportInputs, Outcome::Return(msg));
+
+        (report, inputs)
+    }
+
+    #[test]
+    fn reads_report_with_thousands_of_inputs() {
+
+        let brainstorm = brainstorm();
+        let to = brainstorm.socket.local_addr().unwrap();
+        let stand_in = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let (report, inputs) = large_inputs_report("stand_in");
+        let encoded = report.encode().unwrap();
+        assert!(encoded.len() > 1023, "Report should exceed the old buffer size");
+        assert!(encoded.len() <= MAX_DATAGRAM, "Report should fit in one datagram");
+
+        stand_in.send_to(&encoded, to).unwrap();
+
+        let report = brainstorm.read_report("stand_in", &Action::ReportInputs).unwrap();
+        let Outcome::Return(msg) = report.outcome else { panic!("Expected returned data") };
+        let received: Vec<ReceiverInfo> = bincode::deserialize(&msg).unwrap();
+
+        assert_eq!(received.len(), inputs.len());
+        for (received, sent) in received.iter().zip(inputs.iter()) {
+            assert_eq!(received.tract_name, sent.tract_name);
+            assert_eq!(received.address, sent.address);
+        }
+    }
+
+    #[test]
+    fn discards_unmatched_reports() {
+
+        let brainstorm = brainstorm();
+        let to = brainstorm.socket.local_addr().unwrap();
+        let stand_in = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        // A late reply to an earlier command arrives first
+        let stale = Report::new("stand_in", Action::Status, Outcome::Success);
+        stand_in.send_to(&stale.encode().unwrap(), to).unwrap();
+
+        let (report, inputs) = large_inputs_report("stand_in");
+        stand_in.send_to(&report.encode().unwrap(), to).unwrap();
+
+        let report = brainstorm.read_report("stand_in", &Action::ReportInputs).unwrap();
+        let Outcome::Return(msg) = report.outcome else { panic!("Expected returned data") };
+        let received: Vec<ReceiverInfo> = bincode::deserialize(&msg).unwrap();
+
+        assert_eq!(received.len(), inputs.len());
+    }
+}
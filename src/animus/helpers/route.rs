@@ -0,0 +1,47 @@
+
+use std::net::{ IpAddr, Ipv4Addr, SocketAddr };
+
+use crate::file::{ animi::*, remote::* };
+
+impl crate::Brainstorm {
+
+    // Resolve the address that commands for an animus should be sent to.
+    // Local animi listen on this device, remote animi at their recorded address, 
+    // and unregistered animi at the address they last answered a Query from.
+    // Anything else is assumed to be an unregistered animus on this device.
+    pub(crate) fn route(&self, animus: &str) -> anyhow::Result<SocketAddr> {
+
+        let addr = if local_animus_exists(animus)? {
+            self.local_animus_addr()
+        } else if remote_animus_exists(animus)? {
+            remote_animus_addr(animus, self.config.animus_port)?
+        } else if let Some(addr) = self.discovered.borrow().get(animus) {
+            *addr
+        } else {
+            self.local_animus_addr()
+        };
+
+        Ok(self.destination(addr))
+    }
+
+    // Remember where an animus answered from, to route commands to it later.
+    pub(crate) fn record_discovered(&self, animus: &str, addr: SocketAddr) {
+        self.discovered.borrow_mut().insert(animus.to_string(), addr);
+    }
+
+    // Animi on this device listen on IPv4 loopback.
+    // An IPv6 socket reaches it as `::ffff:127.0.0.1`, through `destination`.
+    fn local_animus_addr(&self) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), self.config.animus_port)
+    }
+
+    // An IPv6 socket reaches IPv4 animi through IPv4-mapped addresses.
+    pub(crate) fn destination(&self, addr: SocketAddr) -> SocketAddr {
+        match (self.config.bind, addr) {
+            (IpAddr::V6(..), SocketAddr::V4(v4)) => {
+                SocketAddr::new(IpAddr::V6(v4.ip().to_ipv6_mapped()), v4.port())
+            },
+            _ => addr,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+
+use std::hash::{ Hash, Hasher };
+use std::path::{ Path, PathBuf };
+use std::time::{ Instant, SystemTime };
+
+use animusd_lib::protocol::{ Action, Outcome };
+use serde_json::json;
+
+// What is known about a network file, to tell whether a save rewrote it.
+#[derive(PartialEq)]
+struct NetworkFingerprint {
+    size: u64,
+    modified: SystemTime,
+    hash: u64,
+}
+
+impl NetworkFingerprint {
+
+    fn read(path: &Path) -> anyhow::Result<Self> {
+
+        let metadata = std::fs::metadata(path)?;
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        std::fs::read(path)?.hash(&mut hasher);
+
+        Ok(NetworkFingerprint {
+            size: metadata.len(),
+            modified: metadata.modified()?,
+            hash: hasher.finish(),
+        })
+    }
+}
+
+impl crate::Brainstorm {
+
+    // Save the network of an animus, which must be asleep to save.
+    // An awake animus is put to sleep and allowed to go quiet first,
+    // then woken again afterward (even if the save failed) unless `wake` is false.
+    pub(crate) fn save_animus(
+        &self,
+        animus: &str,
+        label: Option<String>,
+        note: Option<String>,
+        wake: bool,
+    ) -> anyhow::Result<()> {
+
+        let json = crate::output::is_json();
+
+        // Refuse a taken label before the animus is put to sleep
+        if crate::file::animi::local_animus_exists(animus)? {
+            Self::check_label_unused(animus, &label)?;
+        }
+
+        let was_awake = self.is_awake(animus)?;
+
+        // Any failure from here on still wakes an animus that was awake
+        let saved = if was_awake {
+            if !json { println!("'{}' is awake. Putting it to sleep to save...", animus) }
+            self.request_success(animus, Action::Sleep)
+                .and_then(|_| self.wait_until_drained(animus))
+        } else {
+            Ok(())
+        };
+
+        let saved = saved.and_then(|_| self.save_asleep(animus, label, note));
+
+        if was_awake && wake {
+            let woken = self.request_success(animus, Action::Wake);
+            match (&saved, woken) {
+                (_, Ok(())) => if !json { println!("'{}' is awake again", animus) },
+                (Ok(()), Err(e)) => return Err(e),
+                // The failed save is the error to report
+                (Err(_), Err(e)) => eprintln!("WARN: Could not wake '{}' again: {}", animus, e),
+            }
+        } else if was_awake && !json {
+            println!("'{}' was left asleep", animus)
+        }
+
+        saved
+    }
+
+    // Poll an animus that was told to sleep until it reports that it is asleep,
+    // then give signals already in flight time to be processed before its state is saved.
+    fn wait_until_drained(&self, animus: &str) -> anyhow::Result<()> {
+
+        let deadline = Instant::now() + self.config.sleep_timeout;
+
+        while self.is_awake(animus)? {
+            if Instant::now() >= deadline {
+                return Err(anyhow::anyhow!(
+                    "'{}' was still awake after {}ms. Increase `sleep_timeout` to wait longer.",
+                    animus, self.config.sleep_timeout.as_millis()
+                ))
+            }
+            std::thread::sleep(self.config.backoff);
+        }
+
+        std::thread::sleep(self.config.drain_time);
+        Ok(())
+    }
+
+    // Snapshot the current network, save, and confirm the network file was rewritten.
+    // Remote animi keep their networks on another device, so the file is not checked.
+    fn save_asleep(
+        &self,
+        animus: &str,
+        label: Option<String>,
+        note: Option<String>,
+    ) -> anyhow::Result<()> {
+
+        let json = crate::output::is_json();
+        let local = crate::file::animi::local_animus_exists(animus)?;
+
+        self.snapshot_before_save(animus, label, note)?;
+
+        let network = if local { crate::file::animi::local_network_path(animus)? } else { None };
+        let before = match &network {
+            Some(path) => Some(NetworkFingerprint::read(path)?),
+            None => None,
+        };
+
+        if !json { println!("Saving network state, please wait...") }
+
+        let report = self.request(animus, Action::Save)?;
+        if !matches!(report.outcome, Outcome::Success) {
+            return Err(anyhow::anyhow!(
+                "'{}' did not save: {}",
+                animus, crate::output::outcome_text(report.outcome)
+            ))
+        }
+
+        let changed = if local {
+            Some(Self::check_network_written(animus, network, before)?)
+        } else {
+            None
+        };
+
+        if json {
+            let mut value = crate::output::report_json(&report);
+            value["network_changed"] = json!(changed);
+            crate::output::emit(value);
+            return Ok(())
+        }
+
+        match changed {
+            Some(true) => println!("Saved '{}'", animus),
+            Some(false) => println!("Saved '{}' (the network is unchanged since it was last saved)", animus),
+            None => println!("Saved '{}' (remote, so the network file was not checked)", animus),
+        }
+
+        Ok(())
+    }
+
+    // Check that a save rewrote the network file of a local animus,
+    // returning whether its contents changed.
+    fn check_network_written(
+        animus: &str,
+        network: Option<PathBuf>,
+        before: Option<NetworkFingerprint>,
+    ) -> anyhow::Result<bool> {
+
+        // An animus without a network file should have written a new one
+        let network = match network {
+            Some(network) => network,
+            None => crate::file::animi::local_network_path(animus)?
+                .ok_or_else(|| anyhow::anyhow!(
+                    "'{}' reported a save, but no network file was written", animus
+                ))?,
+        };
+
+        let after = NetworkFingerprint::read(&network)?;
+
+        match before {
+            Some(before) if before == after => Err(anyhow::anyhow!(
+                "'{}' reported a save, but {} was not rewritten",
+                animus, network.display()
+            )),
+            Some(before) => Ok(before.size != after.size || before.hash != after.hash),
+            None => Ok(true),
+        }
+    }
+}
@@ -3,3 +3,7 @@ mod repl;
 mod helpers;
 mod error;
 
+pub(crate) use repl::{ AnimusManagerCli, AnimusCommand };
+pub(crate) use error::RequestError;
+pub(crate) use helpers::MAX_DATAGRAM;
+
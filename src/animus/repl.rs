@@ -6,6 +6,8 @@ use clap_repl::reedline::{ DefaultPrompt, DefaultPromptSegment };
 use animusd_lib::protocol::{ Action, Outcome };
 use cajal_cx::tract::receiver::ReceiverInfo;
 
+use super::RequestError;
+
 
 #[derive(Parser)]
 #[command(
@@ -13,13 +15,13 @@ use cajal_cx::tract::receiver::ReceiverInfo;
     about = "REPL for managing Animus services and networks",
     long_about = "This sub-REPL is used to manage an active Animus service",
 )]
-struct AnimusManagerCli {
+pub(crate) struct AnimusManagerCli {
     #[command(subcommand)]
-    command: AnimusCommand,
+    pub(crate) command: AnimusCommand,
 }
 
 #[derive(Subcommand, Debug)]
-enum AnimusCommand {
+pub(crate) enum AnimusCommand {
 
     /// Retrieve the name of the Complex handled by the Animus.
     /// Animus must be asleep (not processing inputs).
@@ -69,8 +71,24 @@ enum AnimusCommand {
     },
 
     /// Save the state of the Complex to the associated network file.
-    /// Animus must be asleep (not processing inputs).
-    Save,
+    /// The previous network is kept as a snapshot.
+    /// An awake Animus is put to sleep to save, then woken again.
+    Save {
+        #[arg(long, help = 
+            "Label the snapshot of the previous network, to keep it from being pruned."
+        )]
+        label: Option<String>,
+
+        #[arg(long, help = 
+            "Attach a note to the snapshot of the previous network."
+        )]
+        note: Option<String>,
+
+        #[arg(long, action, help = 
+            "Leave an Animus that was awake asleep after saving."
+        )]
+        no_wake: bool,
+    },
 
     /// Begin processing inputs for the Animus.
     /// Animus must be asleep (not processing inputs).
@@ -102,29 +120,8 @@ impl crate::Brainstorm {
         // NOTE Uses a different pattern from MetaCli so we can break 
         loop { match repl.read_command() {
             ReadCommandOutput::Command(cli) => match cli.command {
-                
                 AnimusCommand::Back => { break },
-
-                AnimusCommand::Name => self.handle_command(animus, Action::Name),
-                AnimusCommand::Version => self.handle_command(animus, Action::Version),
-                AnimusCommand::ListStructures => self.handle_command(animus, Action::ListStructures),
-                AnimusCommand::ListInputs => self.handle_command(animus, Action::ListInputs),
-                AnimusCommand::ListOutputs => self.handle_command(animus, Action::ListOutputs),
-                AnimusCommand::InputAddr { tract } => self.animus_input_info(animus, &tract),
-                
-                AnimusCommand::UncheckedLink { tract, port } => {
-                    self.handle_command(animus, Action::UncheckedLink { tract, port })
-                },
-
-                AnimusCommand::Save => {
-                    println!("Saving network state, please wait...");
-                    self.handle_command(animus, Action::Save)
-                },
-
-                AnimusCommand::Wake => self.handle_command(animus, Action::Wake),
-                AnimusCommand::Sleep =>  self.handle_command(animus, Action::Sleep),
-                AnimusCommand::Status => self.handle_command(animus, Action::Status),
-                AnimusCommand::Terminate => self.handle_command(animus, Action::Terminate),
+                command => self.execute_animus_command(animus, command),
             },
 
             ReadCommandOutput::EmptyLine => {/* Continue */},
@@ -137,6 +134,42 @@ impl crate::Brainstorm {
         }}
     }
 
+    // Execute a single animus command, from the REPL or the command line.
+    pub(crate) fn execute_animus_command(&self, animus: &str, command: AnimusCommand) {
+        match command {
+
+            AnimusCommand::Back => {/* Handled by the REPL loop */},
+
+            AnimusCommand::Name => self.handle_command(animus, Action::Name),
+            AnimusCommand::Version => self.handle_command(animus, Action::Version),
+            AnimusCommand::ListStructures => self.handle_command(animus, Action::ListStructures),
+            AnimusCommand::ListInputs => self.handle_command(animus, Action::ListInputs),
+            AnimusCommand::ListOutputs => self.handle_command(animus, Action::ListOutputs),
+            AnimusCommand::InputAddr { tract } => self.animus_input_info(animus, &tract),
+
+            AnimusCommand::UncheckedLink { tract, port } => {
+                self.handle_command(animus, Action::UncheckedLink { tract, port })
+            },
+
+            AnimusCommand::Save { label, note, no_wake } => {
+                if let Err(e) = self.save_animus(animus, label, note, !no_wake) {
+                    Self::animus_command_error(animus, e)
+                }
+            },
+
+            AnimusCommand::Wake => self.handle_command(animus, Action::Wake),
+            AnimusCommand::Sleep =>  self.handle_command(animus, Action::Sleep),
+            AnimusCommand::Status => self.handle_command(animus, Action::Status),
+            AnimusCommand::Terminate => {
+                self.handle_command(animus, Action::Terminate);
+                // An intentional exit, so supervisors should not restart it
+                if let Ok(true) = crate::file::animi::local_animus_exists(animus) {
+                    let _ = crate::file::run::remove_animus_pid(animus);
+                }
+            },
+        }
+    }
+
     fn animus_repl_setup(animus: &str) -> ClapEditor<AnimusManagerCli> {
 
         let prompt = DefaultPrompt {
@@ -153,21 +186,30 @@ impl crate::Brainstorm {
 
     pub(crate) fn handle_command(&self, animus: &str, action: Action) {
 
-        // TODO If local, else if remote send_remote_command,
-        if let Err(e) = self.send_local_command(animus, action.clone()) {
-            Self::animus_command_error(animus, e)
-        } else {
-            if let Err(e) = self.share_response() {
-                Self::animus_response_error(animus, action, e)
-            }
+        match self.request(animus, action.clone()) {
+            Ok(report) => Self::print_report(report),
+            Err(e) => Self::animus_request_error(animus, action, e),
+        }
+    }
+
+    // Log and display an error from a request, depending on where it failed.
+    pub(crate) fn animus_request_error(animus: &str, action: Action, e: RequestError) {
+        match e {
+            RequestError::Send(e) => Self::animus_command_error(animus, e),
+            RequestError::Response(e) => Self::animus_response_error(animus, action, e),
         }
     }
 
     // Log and display an error that occurred while sending an animus command.
     pub(crate) fn animus_command_error(animus: &str, e: anyhow::Error) {
 
-        println!("ERROR: Command to '{}' was not sent properly.", animus);
-        eprintln!("{}", e);
+        if crate::output::is_json() {
+            crate::output::emit(crate::output::animus_error_json(animus, &e))
+        } else {
+            println!("ERROR: Command to '{}' was not sent properly.", animus);
+            eprintln!("{}", e);
+        }
+        crate::report_failure()
     }
 
     // Log and display an error that occurred while awaiting an animus response.
@@ -177,12 +219,18 @@ impl crate::Brainstorm {
         e: anyhow::Error
     ) {
 
-        println!(
-            "ERROR: Socket timed out while waiting for response from '{}'...\n\
-            Command '{}' may not have executed properly.", 
-            animus, action
-        );
-        eprintln!("{}", e);
+        if crate::output::is_json() {
+            let e = anyhow::anyhow!("No response to '{}': {}", action, e);
+            crate::output::emit(crate::output::animus_error_json(animus, &e))
+        } else {
+            println!(
+                "ERROR: No response received from '{}'...\n\
+                Command '{}' may not have executed properly.", 
+                animus, action
+            );
+            eprintln!("{}", e);
+        }
+        crate::report_failure()
     }
 
     fn animus_input_info(&self, animus: &str, tract: &str) {
@@ -190,26 +238,27 @@ impl crate::Brainstorm {
         let action = Action::InputInfo(tract.to_string());
         use bincode::deserialize as de;
 
-        match self.send_local_command(animus, action.clone()) {
-            Err(e) => Self::animus_command_error(animus, e),
-            Ok(..) => match self.read_report() {
-                Err(e) => Self::animus_response_error(animus, action, e),
-                Ok(report) => match report.outcome {
-
-                    Outcome::Return(msg) => match de::<ReceiverInfo>(&msg) {
-                        Err(e) => Self::animus_response_error(
-                            animus, action, 
-                            anyhow::anyhow!("Failed to parse report: {}", e)
-                        ),
-                        Ok(info) => println!("{}", info.address),
-                    },
+        match self.request(animus, action.clone()) {
+            Err(e) => Self::animus_request_error(animus, action, e),
+            Ok(report) => match report.outcome {
 
-                    _ => Self::animus_response_error(
+                Outcome::Return(msg) => match de::<ReceiverInfo>(&msg) {
+                    Err(e) => Self::animus_response_error(
                         animus, action, 
-                        anyhow::anyhow!("Unexpected Outcome variant")
+                        anyhow::anyhow!("Failed to parse report: {}", e)
                     ),
+                    Ok(info) => if crate::output::is_json() {
+                        crate::output::emit(crate::output::receiver_json(&info))
+                    } else {
+                        println!("{}", info.address)
+                    },
+                },
+
+                _ => Self::animus_response_error(
+                    animus, action, 
+                    anyhow::anyhow!("Unexpected Outcome variant")
+                ),
 
-                }
             }
         } 
     }
@@ -16,6 +16,15 @@ pub(crate) fn local_animus_path(name: &str) -> String {
     format!("{}/{}", local.display(), name)
 }
 
+// Where a local animus being overwritten is set aside until its replacement is built.
+// This is outside `local`, so it is never listed as an animus.
+pub(crate) fn replaced_animus_path(name: &str) -> String {
+    let home = std::env::home_dir()
+        .expect("Find user home directory");
+    let replaced = &home.join(".cajal").join("animi").join("replaced");
+    format!("{}/{}", replaced.display(), name)
+}
+
 // Read the animus directory.
 pub(crate) fn read_local_animi() -> anyhow::Result<std::fs::ReadDir> {
     let home = std::env::home_dir()
@@ -56,3 +65,40 @@ pub(crate) fn animus_exists(name: &str) -> anyhow::Result<bool> {
     let exists = local_animus_exists(name)? || remote_animus_exists(name)?;
     Ok(exists)
 }
+
+// Collect the names of all entries in an animus directory.
+fn animus_names(dir: std::fs::ReadDir) -> anyhow::Result<Vec<String>> {
+
+    let mut names = Vec::new();
+    for animus in dir.flatten() {
+        let name = animus.file_name().into_string()
+            .map_err(|_| anyhow::anyhow!("Couldn't read OsString"))?;
+        names.push(name)
+    }
+
+    Ok(names)
+}
+
+// Get the names of all animi with local data.
+pub(crate) fn local_animus_names() -> anyhow::Result<Vec<String>> {
+    animus_names(read_local_animi()?)
+}
+
+// Get the names of all animi with remote records.
+pub(crate) fn remote_animus_names() -> anyhow::Result<Vec<String>> {
+    animus_names(read_remote_animi()?)
+}
+
+// Find the network file an animus loads and saves over, in its `bin` directory.
+pub(crate) fn local_network_path(name: &str) -> anyhow::Result<Option<std::path::PathBuf>> {
+
+    let bin = format!("{}/bin", local_animus_path(name));
+    if ! std::path::Path::new(&bin).is_dir() { return Ok(None) }
+
+    let network = std::fs::read_dir(bin)?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "nn"));
+
+    Ok(network)
+}
@@ -0,0 +1,18 @@
+
+//! Helper functions for archives of deanimated animi
+
+// Create a string representing the path to the archive directory.
+pub(crate) fn archive_dir() -> String {
+    let home = std::env::home_dir()
+        .expect("Find user home directory");
+    let archive = &home.join(".cajal").join("brainstorm").join("archive");
+    format!("{}", archive.display())
+}
+
+// Path of a new archive for an animus, e.g., `my_network-1700000000.tar.gz`
+pub(crate) fn new_archive_path(animus: &str) -> anyhow::Result<String> {
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    Ok(format!("{}/{}-{}.tar.gz", archive_dir(), animus, time))
+}
@@ -0,0 +1,89 @@
+
+//! Helper functions for the cache of built animusd executables
+
+use crate::file::service::BuildOptions;
+
+// Create a string representing the path to the build cache.
+pub(crate) fn cache_dir() -> String {
+    let home = std::env::home_dir()
+        .expect("Find user home directory");
+    let cache = &home.join(".cajal").join("brainstorm").join("cache");
+    format!("{}", cache.display())
+}
+
+// Path of the cached executable for a release of animusd, built with these features.
+// e.g., `animusd-0.3.1+logging`
+pub(crate) fn cached_animusd_path(version: &str, features: &[String]) -> String {
+    format!("{}/animusd-{}{}", cache_dir(), version, feature_suffix(features))
+}
+
+// Features are kept sorted in the file name, so the order they are given in doesn't matter.
+fn feature_suffix(features: &[String]) -> String {
+
+    let mut features = features.to_vec();
+    features.sort();
+    features.dedup();
+
+    features.iter().map(|feature| format!("+{}", feature)).collect()
+}
+
+// Directory that cargo installs into before the executable is cached.
+pub(crate) fn staging_dir(animus: &str) -> String {
+    format!("{}/staging-{}", cache_dir(), animus)
+}
+
+// Find a cached executable matching the options, if they are for a crates.io release.
+// Without a pinned version, the newest cached release with the same features is used,
+// unless the options ask for the latest release.
+pub(crate) fn find_cached_animusd(options: &BuildOptions) -> Option<String> {
+
+    if options.path.is_some() || options.git.is_some() { return None }
+
+    if let Some(version) = &options.version {
+        let path = cached_animusd_path(version, &options.features);
+        return std::path::Path::new(&path).exists().then_some(path)
+    }
+
+    if options.latest { return None }
+
+    let suffix = feature_suffix(&options.features);
+    let newest = cached_versions().into_iter()
+        .filter(|(_, features)| *features == suffix)
+        .map(|(version, _)| version)
+        .max_by_key(|version| version_key(version))?;
+
+    Some(cached_animusd_path(&newest, &options.features))
+}
+
+// The (version, feature suffix) of each cached executable.
+fn cached_versions() -> Vec<(String, String)> {
+
+    let Ok(entries) = std::fs::read_dir(cache_dir()) else { return Vec::new() };
+
+    entries.flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let cached = name.strip_prefix("animusd-")?;
+            let (version, features) = match cached.find('+') {
+                Some(i) => cached.split_at(i),
+                None => (cached, ""),
+            };
+            Some((version.to_string(), features.to_string()))
+        })
+        .collect()
+}
+
+// Order versions by their numbers, with pre-releases before the release itself.
+fn version_key(version: &str) -> (Vec<u64>, bool) {
+
+    let (numbers, pre_release) = match version.split_once('-') {
+        Some((numbers, _)) => (numbers, true),
+        None => (version, false),
+    };
+
+    let numbers = numbers.split('.')
+        .map(|n| n.parse().unwrap_or(0))
+        .collect();
+
+    (numbers, !pre_release)
+}
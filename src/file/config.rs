@@ -0,0 +1,118 @@
+
+//! Helper functions for reading the Brainstorm config
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+
+// Settings read from ~/.cajal/brainstorm/brainstorm.cfg
+#[derive(Debug, Clone)]
+pub(crate) struct BrainstormConfig {
+    /// Address that Brainstorm receives reports on. Use `::` for IPv6.
+    pub(crate) bind: IpAddr,
+    /// Port that Brainstorm receives reports on. If unset, the REPL uses
+    /// `REPORT_PORT`, and one-shot commands and daemons use any free port.
+    pub(crate) port: Option<u16>,
+    /// Port that animi listen on, unless a remote record gives another.
+    pub(crate) animus_port: u16,
+    /// How long to wait for a report before giving up on an attempt.
+    pub(crate) timeout: Duration,
+    /// How long to wait for the report to a Save, which can be slow.
+    pub(crate) save_timeout: Duration,
+    /// How many times to resend idempotent commands that receive no report.
+    pub(crate) retries: u32,
+    /// Delay before the first retry, doubled for each retry after.
+    pub(crate) backoff: Duration,
+    /// How long a newly loaded animus has to begin answering.
+    pub(crate) launch_timeout: Duration,
+    /// How long an animus put to sleep for a save has to report that it is asleep.
+    pub(crate) sleep_timeout: Duration,
+    /// How long to let activity drain after an animus is asleep, before saving it.
+    pub(crate) drain_time: Duration,
+    /// How many unlabeled network snapshots to keep for each animus, or 0 for all.
+    pub(crate) snapshot_retention: usize,
+}
+
+impl Default for BrainstormConfig {
+    fn default() -> Self {
+        BrainstormConfig {
+            bind: IpAddr::from([127, 0, 0, 1]),
+            port: None,
+            animus_port: crate::ANIMUS_PORT,
+            timeout: Duration::from_millis(2000),
+            save_timeout: Duration::from_millis(30000),
+            retries: 2,
+            backoff: Duration::from_millis(200),
+            launch_timeout: Duration::from_millis(10000),
+            sleep_timeout: Duration::from_millis(5000),
+            drain_time: Duration::from_millis(5000),
+            snapshot_retention: 10,
+        }
+    }
+}
+
+// Create a string representing the path to the Brainstorm config file.
+pub(crate) fn brainstorm_config_path() -> String {
+    let home = std::env::home_dir()
+        .expect("Find user home directory");
+    let brain = &home.join(".cajal").join("brainstorm");
+    format!("{}/brainstorm.cfg", brain.display())
+}
+
+// Read the config file, using defaults for anything not set.
+// The file is made of `key = value` lines, and `#` begins a comment.
+pub(crate) fn read_brainstorm_config() -> anyhow::Result<BrainstormConfig> {
+
+    let mut config = BrainstormConfig::default();
+
+    let path = brainstorm_config_path();
+    if ! std::path::Path::new(&path).exists() { return Ok(config) }
+
+    let file = std::fs::read_to_string(&path)?;
+    for (number, line) in file.lines().enumerate() {
+
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(anyhow::anyhow!(
+                "{}:{}: Expected `key = value`", path, number + 1
+            ))
+        };
+
+        let (key, value) = (key.trim(), value.trim());
+        set_config_value(&mut config, key, value)
+            .map_err(|e| anyhow::anyhow!("{}:{}: {}", path, number + 1, e))?;
+    }
+
+    Ok(config)
+}
+
+// Parse a value into the setting named by the key.
+fn set_config_value(
+    config: &mut BrainstormConfig,
+    key: &str,
+    value: &str
+) -> anyhow::Result<()> {
+
+    let millis = || -> anyhow::Result<Duration> {
+        Ok(Duration::from_millis(value.parse::<u64>()?))
+    };
+
+    match key {
+        "bind" => config.bind = value.parse()?,
+        "port" => config.port = Some(value.parse()?),
+        "animus_port" => config.animus_port = value.parse()?,
+        "timeout" => config.timeout = millis()?,
+        "save_timeout" => config.save_timeout = millis()?,
+        "retries" => config.retries = value.parse()?,
+        "backoff" => config.backoff = millis()?,
+        "launch_timeout" => config.launch_timeout = millis()?,
+        "sleep_timeout" => config.sleep_timeout = millis()?,
+        "drain_time" => config.drain_time = millis()?,
+        "snapshot_retention" => config.snapshot_retention = value.parse()?,
+        _ => return Err(anyhow::anyhow!("Unknown setting '{}'", key)),
+    }
+
+    Ok(())
+}
@@ -9,6 +9,19 @@ pub(crate) fn read_groups() -> anyhow::Result<std::fs::ReadDir> {
     Ok(std::fs::read_dir(groups)?)
 }
 
+// Get the names of all groups.
+pub(crate) fn group_names() -> anyhow::Result<Vec<String>> {
+
+    let mut names = Vec::new();
+    for group in read_groups()?.flatten() {
+        let name = group.file_name().into_string()
+            .map_err(|_| anyhow::anyhow!("Couldn't read OsString"))?;
+        names.push(name)
+    }
+
+    Ok(names)
+}
+
 // Check if a network binary with the given name exists in the `saved`` folder.
 // Expects that the filesystem is correctly configured and readable.
 pub(crate) fn group_exists(group_name: &str) -> anyhow::Result<bool> {
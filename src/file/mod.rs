@@ -6,4 +6,12 @@ pub(crate) mod animi;
 pub(crate) mod saved;
 pub(crate) mod groups;
 pub(crate) mod remote;
+pub(crate) mod config;
+pub(crate) mod run;
+pub(crate) mod service;
+pub(crate) mod cache;
+pub(crate) mod archive;
+pub(crate) mod snapshot;
+pub(crate) mod schedule;
+pub(crate) mod systemd;
 
@@ -6,13 +6,23 @@ pub(crate) fn remote_animus_path(name: &str) -> String {
     format!("{}/{}", remote.display(), name)
 }
 
-pub(crate) fn remote_animus_ip(name: &str) -> anyhow::Result<std::net::IpAddr> {
+// Read the socket address of a remote animus from its record.
+// Records that only contain an IP address use the default animus port.
+pub(crate) fn remote_animus_addr(
+    name: &str, 
+    default_port: u16
+) -> anyhow::Result<std::net::SocketAddr> {
+
     let path = remote_animus_path(name);
     match std::fs::read_to_string(path) {
         Ok(addr) => {
+            let addr = addr.trim();
+            if let Ok(addr) = addr.parse::<std::net::SocketAddr>() {
+                return Ok(addr)
+            }
             match addr.parse::<std::net::IpAddr>() {
-                Ok(addr) => {
-                    Ok(addr)
+                Ok(ip) => {
+                    Ok(std::net::SocketAddr::new(ip, default_port))
                 },
                 Err(e) => {
                     Err(anyhow::anyhow!("{}", e))
@@ -25,10 +35,15 @@ pub(crate) fn remote_animus_ip(name: &str) -> anyhow::Result<std::net::IpAddr> {
     } 
 }
 
-pub(crate) fn write_remote_animus(name: &str, addr: std::net::IpAddr) -> anyhow::Result<()> {
+pub(crate) fn write_remote_animus(name: &str, addr: std::net::SocketAddr) -> anyhow::Result<()> {
     let path = remote_animus_path(name);
     std::fs::write(path, addr.to_string())?;
     Ok(())
 }
 
+pub(crate) fn remove_remote_animus(name: &str) -> anyhow::Result<()> {
+    let path = remote_animus_path(name);
+    std::fs::remove_file(path)?;
+    Ok(())
+}
 
@@ -0,0 +1,97 @@
+
+//! Helper functions for animus runtime files (PID files, exit statuses and logs)
+
+// Number of old logs kept for each stream when an animus is relaunched.
+const KEPT_LOGS: usize = 5;
+
+// Create a string representing the path to an animus's runtime directory.
+pub(crate) fn animus_run_dir(name: &str) -> String {
+    format!("{}/run", crate::file::animi::local_animus_path(name))
+}
+
+// Create a string representing the path to an animus's log directory.
+pub(crate) fn animus_log_dir(name: &str) -> String {
+    format!("{}/logs", crate::file::animi::local_animus_path(name))
+}
+
+pub(crate) fn animus_pid_path(name: &str) -> String {
+    format!("{}/animusd.pid", animus_run_dir(name))
+}
+
+// Paths of the current stdout and stderr logs for an animus.
+pub(crate) fn animus_log_paths(name: &str) -> (String, String) {
+    let logs = animus_log_dir(name);
+    (format!("{}/animusd.out", logs), format!("{}/animusd.err", logs))
+}
+
+// The exit status of the last animus process that was reaped, as "<pid> <status>".
+pub(crate) fn animus_exit_path(name: &str) -> String {
+    format!("{}/animusd.exit", animus_run_dir(name))
+}
+
+pub(crate) fn write_animus_pid(name: &str, pid: u32) -> anyhow::Result<()> {
+    std::fs::create_dir_all(animus_run_dir(name))?;
+    std::fs::write(animus_pid_path(name), pid.to_string())?;
+    Ok(())
+}
+
+// Read the PID recorded when the animus was last launched, if any.
+pub(crate) fn read_animus_pid(name: &str) -> anyhow::Result<Option<u32>> {
+
+    let path = animus_pid_path(name);
+    if ! std::path::Path::new(&path).exists() { return Ok(None) }
+
+    let pid = std::fs::read_to_string(path)?.trim().parse()?;
+    Ok(Some(pid))
+}
+
+pub(crate) fn remove_animus_pid(name: &str) -> anyhow::Result<()> {
+    let path = animus_pid_path(name);
+    if std::path::Path::new(&path).exists() {
+        std::fs::remove_file(path)?
+    }
+    Ok(())
+}
+
+// Record how an animus process exited.
+// Nothing is written if the animus was deanimated or renamed while it ran.
+pub(crate) fn write_animus_exit(name: &str, pid: u32, status: &str) -> anyhow::Result<()> {
+    let run = animus_run_dir(name);
+    if ! std::path::Path::new(&run).is_dir() { return Ok(()) }
+    std::fs::write(animus_exit_path(name), format!("{} {}", pid, status))?;
+    Ok(())
+}
+
+// Read the exit status recorded for the process with this PID, if it was reaped.
+pub(crate) fn read_animus_exit(name: &str, pid: u32) -> anyhow::Result<Option<String>> {
+
+    let path = animus_exit_path(name);
+    if ! std::path::Path::new(&path).exists() { return Ok(None) }
+
+    let recorded = std::fs::read_to_string(path)?;
+    Ok(recorded.trim().split_once(' ')
+        .filter(|(recorded_pid, _)| *recorded_pid == pid.to_string())
+        .map(|(_, status)| status.to_string()))
+}
+
+// Shift existing logs to make room for a new launch.
+// `animusd.out` becomes `animusd.out.1`, and so on, up to the number kept.
+pub(crate) fn rotate_animus_logs(name: &str) -> anyhow::Result<()> {
+
+    std::fs::create_dir_all(animus_log_dir(name))?;
+
+    let (out, err) = animus_log_paths(name);
+    for log in [out, err] {
+        for n in (1..KEPT_LOGS).rev() {
+            let older = format!("{}.{}", log, n);
+            if std::path::Path::new(&older).exists() {
+                std::fs::rename(&older, format!("{}.{}", log, n + 1))?
+            }
+        }
+        if std::path::Path::new(&log).exists() {
+            std::fs::rename(&log, format!("{}.1", log))?
+        }
+    }
+
+    Ok(())
+}
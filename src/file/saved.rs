@@ -1,6 +1,10 @@
 
 //! Helper functions for reading saved networks
 
+use std::path::{ Path, PathBuf };
+
+use cajal_cx::complex::Complex;
+
 // Read the saved networks directory.
 pub(crate) fn read_saved() -> anyhow::Result<std::fs::ReadDir> {
     let home = std::env::home_dir()
@@ -13,9 +17,10 @@ pub(crate) fn read_saved() -> anyhow::Result<std::fs::ReadDir> {
 // Expects that the filesystem is correctly configured and readable.
 pub(crate) fn network_exists(network_name: &str) -> anyhow::Result<bool> {
 
+    let file_name = format!("{}.nn", network_name);
     let exists = read_saved()?
         .flatten()
-        .any(|f| f.file_name() == network_name);
+        .any(|f| f.file_name() == file_name.as_str());
 
     Ok(exists)
 }
@@ -28,3 +33,33 @@ pub(crate) fn network_path(network_name: &str) -> String {
     format!("{}/{}.nn", saved.display(), network_name)
 }
 
+
+// Find the file for a network, given either the name of a saved network
+// (with or without `.nn`) or a path to a network file anywhere.
+// Returns the network name along with the path.
+pub(crate) fn resolve_network(network: &Path) -> (String, PathBuf) {
+
+    let is_path = network.components().count() > 1 || network.is_file();
+    let name = network.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if is_path {
+        (name, network.to_path_buf())
+    } else {
+        let path = PathBuf::from(network_path(&name));
+        (name, path)
+    }
+}
+
+// Read a network file, checking that it deserializes as a cajal Complex.
+pub(crate) fn read_network(path: &Path) -> anyhow::Result<Complex> {
+
+    let bytes = std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("Can't read '{}': {}", path.display(), e))?;
+
+    let complex = bincode::deserialize(&bytes)
+        .map_err(|e| anyhow::anyhow!("'{}' is not a valid network: {}", path.display(), e))?;
+
+    Ok(complex)
+}
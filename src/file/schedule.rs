@@ -0,0 +1,89 @@
+
+//! Helper functions for the schedule of recurring animus commands
+
+use std::io::Write;
+
+// A recurring command, e.g., `0 */6 * * * save my_network`
+#[derive(Debug, Clone)]
+pub(crate) struct ScheduleEntry {
+    /// Five cron fields: minute, hour, day of month, month, day of week.
+    pub(crate) cron: String,
+    pub(crate) action: String,
+    /// An animus name, or `group:<name>` for every member of a group.
+    pub(crate) target: String,
+}
+
+// Create a string representing the path to the schedule file.
+pub(crate) fn schedule_path() -> String {
+    let home = std::env::home_dir()
+        .expect("Find user home directory");
+    let brain = &home.join(".cajal").join("brainstorm");
+    format!("{}/schedule.cfg", brain.display())
+}
+
+// Create a string representing the path to the record of scheduled runs.
+pub(crate) fn schedule_log_path() -> String {
+    let home = std::env::home_dir()
+        .expect("Find user home directory");
+    let brain = &home.join(".cajal").join("brainstorm");
+    format!("{}/schedule.log", brain.display())
+}
+
+// Read the schedule, one entry per line: cron fields, then action, then target.
+// `#` begins a comment.
+pub(crate) fn read_schedule() -> anyhow::Result<Vec<ScheduleEntry>> {
+
+    let path = schedule_path();
+    if ! std::path::Path::new(&path).exists() { return Ok(Vec::new()) }
+
+    let mut entries = Vec::new();
+    let file = std::fs::read_to_string(&path)?;
+    for (number, line) in file.lines().enumerate() {
+
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 7 {
+            return Err(anyhow::anyhow!(
+                "{}:{}: Expected `minute hour day month weekday action target`",
+                path, number + 1
+            ))
+        }
+
+        entries.push(ScheduleEntry {
+            cron: fields[..5].join(" "),
+            action: fields[5].to_string(),
+            target: fields[6].to_string(),
+        })
+    }
+
+    Ok(entries)
+}
+
+pub(crate) fn write_schedule(entries: &[ScheduleEntry]) -> anyhow::Result<()> {
+
+    let mut file = String::from("# minute hour day month weekday (UTC)  action  target\n");
+    for entry in entries.iter() {
+        file.push_str(&format!("{}  {}  {}\n", entry.cron, entry.action, entry.target))
+    }
+
+    std::fs::write(schedule_path(), file)?;
+    Ok(())
+}
+
+// Append the outcome of a scheduled run to the record, with a Unix timestamp.
+pub(crate) fn record_run(entry: &ScheduleEntry, animus: &str, outcome: &str) -> anyhow::Result<()> {
+
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+
+    let mut log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(schedule_log_path())?;
+
+    writeln!(log, "{} {} {} {}", time, entry.action, animus, outcome)?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+
+//! Helper functions for reading and editing an animus's service config
+
+// Keys that Brainstorm keeps in `service.cfg`. All other lines are left to animusd.
+const BUILD_KEYS: [&str; 5] = [
+    "animusd_version", "animusd_features", "animusd_path", "animusd_git", "animusd_binary"
+];
+
+// How the animusd executable for an animus is built,
+// so that `rebuild` and `upgrade` install it the same way.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BuildOptions {
+    /// Version of animusd from crates.io, or the latest if unset.
+    pub(crate) version: Option<String>,
+    /// Cargo features to enable, e.g., `logging`.
+    pub(crate) features: Vec<String>,
+    /// Local source directory to install from instead of crates.io.
+    pub(crate) path: Option<String>,
+    /// Git repository to install from instead of crates.io.
+    pub(crate) git: Option<String>,
+    /// Prebuilt executable to use instead of building one.
+    pub(crate) binary: Option<String>,
+    /// Ask crates.io for the latest release instead of reusing a cached build.
+    /// This is not kept in `service.cfg`.
+    pub(crate) latest: bool,
+}
+
+impl BuildOptions {
+    // Where animusd is installed from, for display.
+    pub(crate) fn source(&self) -> String {
+        if let Some(binary) = &self.binary { return format!("from {}", binary) }
+
+        match (&self.path, &self.git, &self.version) {
+            (Some(path), _, _) => format!("from {}", path),
+            (None, Some(git), _) => format!("from {}", git),
+            (None, None, Some(version)) => format!("v{}", version),
+            (None, None, None) => "(latest)".to_string(),
+        }
+    }
+}
+
+// Create a string representing the path to the service config for an animus.
+pub(crate) fn service_config_path(name: &str) -> String {
+    format!("{}/service.cfg", crate::file::animi::local_animus_path(name))
+}
+
+// Read the build settings from `service.cfg`, using defaults for anything not set.
+// Lines are `key = value`, and `#` begins a comment.
+pub(crate) fn read_build_options(name: &str) -> anyhow::Result<BuildOptions> {
+
+    let mut options = BuildOptions::default();
+
+    let path = service_config_path(name);
+    if ! std::path::Path::new(&path).exists() { return Ok(options) }
+
+    let file = std::fs::read_to_string(&path)?;
+    for line in file.lines() {
+
+        let line = line.split('#').next().unwrap_or("").trim();
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let (key, value) = (key.trim(), value.trim().to_string());
+
+        match key {
+            "animusd_version" => options.version = Some(value),
+            "animusd_features" => {
+                options.features = value.split(',')
+                    .map(|f| f.trim().to_string())
+                    .filter(|f| !f.is_empty())
+                    .collect()
+            },
+            "animusd_path" => options.path = Some(value),
+            "animusd_git" => options.git = Some(value),
+            "animusd_binary" => options.binary = Some(value),
+            _ => {/* Service settings */},
+        }
+    }
+
+    Ok(options)
+}
+
+// Write the build settings into `service.cfg`, keeping every other line.
+pub(crate) fn write_build_options(name: &str, options: &BuildOptions) -> anyhow::Result<()> {
+
+    let path = service_config_path(name);
+    let file = if std::path::Path::new(&path).exists() {
+        std::fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+
+    let mut lines: Vec<String> = file.lines()
+        .filter(|line| {
+            let key = line.split('=').next().unwrap_or("").trim();
+            !BUILD_KEYS.contains(&key)
+        })
+        .map(|line| line.to_string())
+        .collect();
+
+    if let Some(version) = &options.version {
+        lines.push(format!("animusd_version = {}", version))
+    }
+    if !options.features.is_empty() {
+        lines.push(format!("animusd_features = {}", options.features.join(",")))
+    }
+    if let Some(source) = &options.path {
+        lines.push(format!("animusd_path = {}", source))
+    }
+    if let Some(git) = &options.git {
+        lines.push(format!("animusd_git = {}", git))
+    }
+    if let Some(binary) = &options.binary {
+        lines.push(format!("animusd_binary = {}", binary))
+    }
+
+    std::fs::write(path, lines.join("\n") + "\n")?;
+    Ok(())
+}
@@ -0,0 +1,134 @@
+
+//! Helper functions for snapshots of an animus's network
+
+use std::path::Path;
+
+// A copy of an animus's network, taken before it was saved over.
+pub(crate) struct Snapshot {
+    pub(crate) number: u32,
+    /// Seconds since the Unix epoch.
+    pub(crate) time: u64,
+    pub(crate) size: u64,
+    pub(crate) label: Option<String>,
+    pub(crate) note: Option<String>,
+}
+
+// Create a string representing the path to an animus's snapshot directory.
+pub(crate) fn snapshot_dir(name: &str) -> String {
+    format!("{}/snapshots", crate::file::animi::local_animus_path(name))
+}
+
+// Path of the network copy for a snapshot.
+pub(crate) fn snapshot_network_path(name: &str, number: u32) -> String {
+    format!("{}/{}.nn", snapshot_dir(name), number)
+}
+
+// Path of the `key = value` file describing a snapshot.
+fn snapshot_info_path(name: &str, number: u32) -> String {
+    format!("{}/{}.info", snapshot_dir(name), number)
+}
+
+// Read every snapshot of an animus, oldest first.
+pub(crate) fn read_snapshots(name: &str) -> anyhow::Result<Vec<Snapshot>> {
+
+    let dir = snapshot_dir(name);
+    if ! Path::new(&dir).is_dir() { return Ok(Vec::new()) }
+
+    let mut snapshots = Vec::new();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "info") { continue }
+
+        let Some(number) = path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse().ok())
+            else { continue };
+
+        snapshots.push(read_snapshot(name, number)?)
+    }
+
+    snapshots.sort_by_key(|s| s.number);
+    Ok(snapshots)
+}
+
+fn read_snapshot(name: &str, number: u32) -> anyhow::Result<Snapshot> {
+
+    let mut snapshot = Snapshot { number, time: 0, size: 0, label: None, note: None };
+
+    let info = std::fs::read_to_string(snapshot_info_path(name, number))?;
+    for line in info.lines() {
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.trim().to_string();
+
+        match key.trim() {
+            "time" => snapshot.time = value.parse()?,
+            "label" => snapshot.label = Some(value),
+            "note" => snapshot.note = Some(value),
+            _ => {/* Ignore */},
+        }
+    }
+
+    snapshot.size = std::fs::metadata(snapshot_network_path(name, number))
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    Ok(snapshot)
+}
+
+// Copy the network of an animus into a new snapshot.
+// Returns `None` if the animus has no network file to copy.
+pub(crate) fn take_snapshot(
+    name: &str,
+    label: Option<String>,
+    note: Option<String>,
+) -> anyhow::Result<Option<Snapshot>> {
+
+    let Some(network) = crate::file::animi::local_network_path(name)? else {
+        return Ok(None)
+    };
+
+    let number = read_snapshots(name)?.last().map_or(1, |s| s.number + 1);
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+
+    std::fs::create_dir_all(snapshot_dir(name))?;
+    let size = std::fs::copy(&network, snapshot_network_path(name, number))?;
+
+    // Newlines would break the info file
+    let label = label.map(|l| l.replace('\n', " "));
+    let note = note.map(|n| n.replace('\n', " "));
+
+    let mut info = format!("time = {}\n", time);
+    if let Some(label) = &label { info.push_str(&format!("label = {}\n", label)) }
+    if let Some(note) = &note { info.push_str(&format!("note = {}\n", note)) }
+    std::fs::write(snapshot_info_path(name, number), info)?;
+
+    Ok(Some(Snapshot { number, time, size, label, note }))
+}
+
+// Delete the oldest unlabeled snapshots, keeping the given number of them.
+// Labeled snapshots are always kept. Returns how many were deleted.
+pub(crate) fn prune_snapshots(name: &str, keep: usize) -> anyhow::Result<usize> {
+
+    let unlabeled: Vec<Snapshot> = read_snapshots(name)?.into_iter()
+        .filter(|s| s.label.is_none())
+        .collect();
+
+    let excess = unlabeled.len().saturating_sub(keep);
+    for snapshot in unlabeled.iter().take(excess) {
+        std::fs::remove_file(snapshot_network_path(name, snapshot.number))?;
+        std::fs::remove_file(snapshot_info_path(name, snapshot.number))?;
+    }
+
+    Ok(excess)
+}
+
+// Find a snapshot by its number or label.
+pub(crate) fn find_snapshot(name: &str, key: &str) -> anyhow::Result<Option<Snapshot>> {
+
+    let snapshot = read_snapshots(name)?.into_iter()
+        .find(|s| s.number.to_string() == key || s.label.as_deref() == Some(key));
+
+    Ok(snapshot)
+}
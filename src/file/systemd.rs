@@ -0,0 +1,54 @@
+
+//! Helper functions for systemd user unit files
+
+// Create a string representing the path to the systemd user unit directory.
+pub(crate) fn unit_dir() -> String {
+    let home = std::env::home_dir()
+        .expect("Find user home directory");
+    let units = &home.join(".config").join("systemd").join("user");
+    format!("{}", units.display())
+}
+
+pub(crate) fn unit_path(unit: &str) -> String {
+    format!("{}/{}", unit_dir(), unit)
+}
+
+// Name of the service unit that runs a local animus.
+pub(crate) fn animus_unit_name(animus: &str) -> String {
+    format!("animusd-{}.service", animus)
+}
+
+// Name of the target unit that brings up every member of a group.
+pub(crate) fn group_target_name(group: &str) -> String {
+    format!("brainstorm-group-{}.target", group)
+}
+
+// Name of the service unit that links a group once its members are up.
+pub(crate) fn group_link_name(group: &str) -> String {
+    format!("brainstorm-group-{}-link.service", group)
+}
+
+// Check if a name can be used in a unit name without escaping.
+// (a-Z, 0-9, underscores and dashes)
+pub(crate) fn valid_unit_name(name: &str) -> bool {
+    if name.is_empty() { return false }
+    name.chars().all(|c| { c.is_ascii_alphanumeric() || c == '_' || c == '-' })
+}
+
+pub(crate) fn unit_exists(unit: &str) -> bool {
+    std::path::Path::new(&unit_path(unit)).exists()
+}
+
+pub(crate) fn write_unit(unit: &str, contents: &str) -> anyhow::Result<()> {
+    std::fs::create_dir_all(unit_dir())?;
+    std::fs::write(unit_path(unit), contents)?;
+    Ok(())
+}
+
+pub(crate) fn remove_unit(unit: &str) -> anyhow::Result<()> {
+    let path = unit_path(unit);
+    if std::path::Path::new(&path).exists() {
+        std::fs::remove_file(path)?
+    }
+    Ok(())
+}
@@ -1,5 +1,6 @@
 
 use std::collections::HashMap;
+use serde_json::json;
 use cajal_cx::tract::receiver::ReceiverInfo;
 use animusd_lib::protocol::{ Action, Outcome };
 
@@ -23,8 +24,8 @@ impl crate::Brainstorm {
                 self.gather_animus_outputs(animus, &mut senders);
             }
 
-            self.attempt_link_tracts(&mut senders, &mut receivers)
-                .report_unlinked_tracts(senders, receivers);
+            let linked = self.attempt_link_tracts(&mut senders, &mut receivers);
+            Self::report_autolink(group, linked, senders, receivers);
         }
 
     }
@@ -33,6 +34,7 @@ impl crate::Brainstorm {
         let read = crate::file::groups::read_group_members(group);
         if let Err(e) = &read {
             println!("Group file for '{}' is corrupted or missing: {}", group, e);
+            crate::report_failure();
             return None
         }
         Some(read.unwrap())
@@ -56,6 +58,7 @@ impl crate::Brainstorm {
                             group
                         );
 
+                        crate::report_failure();
                         return false
                     }
                 },
@@ -75,6 +78,7 @@ impl crate::Brainstorm {
                             group
                         );
 
+                        crate::report_failure();
                         return false
                     }
                 },
@@ -95,48 +99,47 @@ impl crate::Brainstorm {
     ) {
         let action = Action::ReportInputs;
 
-        if let Err(e) = self.send_command(animus, action.clone()) {
-            return Self::animus_command_error(animus, e)
-        } else {
-            match self.read_report() {
-                Err(e) => return Self::animus_response_error(animus, action, e),
-                Ok(report) => {
+        match self.request(animus, action.clone()) {
+            Err(e) => return Self::animus_request_error(animus, action, e),
+            Ok(report) => {
 
-                    match report.outcome {
-                        Outcome::Return(msg) => {
+                match report.outcome {
+                    Outcome::Return(msg) => {
 
-                            let msg = bincode::deserialize(&msg);
-                            if let Err(e) = &msg {
+                        let msg = bincode::deserialize(&msg);
+                        if let Err(e) = &msg {
+                            crate::report_failure();
+                            return println!(
+                                "Failed to deserialize a Report's Outcome::Return data. \
+                                Check the version of `animusd-lib` you are using. \
+                                {}", e
+                            )
+                        }
+                        let list: Vec<ReceiverInfo> = msg.unwrap();
+
+                        for info in list.iter() {
+                            let tract_name = &info.tract_name;
+                            if let Some(..) = receivers.insert(
+                                tract_name.clone(), 
+                                (animus.to_string(), info.clone())
+                            ) {
+                                crate::report_failure();
                                 return println!(
-                                    "Failed to deserialize a Report's Outcome::Return data. \
-                                    Check the version of `animusd-lib` you are using. \
-                                    {}", e
+                                    "ERROR: Aborting auto-link: Duplicate of Input '{}' found",
+                                    tract_name
                                 )
                             }
-                            let list: Vec<ReceiverInfo> = msg.unwrap();
-
-                            for info in list.iter() {
-                                let tract_name = &info.tract_name;
-                                if let Some(..) = receivers.insert(
-                                    tract_name.clone(), 
-                                    (animus.to_string(), info.clone())
-                                ) {
-                                    return println!(
-                                        "ERROR: Aborting auto-link: Duplicate of Input '{}' found",
-                                        tract_name
-                                    )
-                                }
-                            }
-                        },
-                        _ => {
-                            return println!(
-                                "ERROR: Unexpected Outcome violates protocol. \
-                                Check the version of `animusd-lib` you are using."
-                            )
                         }
+                    },
+                    _ => {
+                        crate::report_failure();
+                        return println!(
+                            "ERROR: Unexpected Outcome violates protocol. \
+                            Check the version of `animusd-lib` you are using."
+                        )
                     }
-
                 }
+
             }
         }
 
@@ -150,73 +153,130 @@ impl crate::Brainstorm {
 
         let action = Action::ListOutputs;
 
-        if let Err(e) = self.send_command(animus, action.clone()) {
-            return Self::animus_command_error(animus, e)
-        } else {
-            match self.read_report() {
-                Err(e) => return Self::animus_response_error(animus, action, e),
-                Ok(report) => {
+        match self.request(animus, action.clone()) {
+            Err(e) => return Self::animus_request_error(animus, action, e),
+            Ok(report) => {
 
-                    match report.outcome {
-                        Outcome::Return(msg) => {
+                match report.outcome {
+                    Outcome::Return(msg) => {
 
-                            let msg = bincode::deserialize(&msg);
-                            if let Err(e) = &msg {
+                        let msg = bincode::deserialize(&msg);
+                        if let Err(e) = &msg {
+                            crate::report_failure();
+                            return println!(
+                                "Failed to deserialize a Report's Outcome::Return data. \
+                                Check the version of `animusd-lib` you are using. \
+                                {}", e
+                            )
+                        }
+                        let list: Vec<String> = msg.unwrap();
+
+                        for output in list.iter() {
+                            if let Some(..) = senders.insert(
+                                output.clone(), animus.to_string() 
+                            ) {
+                                crate::report_failure();
                                 return println!(
-                                    "Failed to deserialize a Report's Outcome::Return data. \
-                                    Check the version of `animusd-lib` you are using. \
-                                    {}", e
+                                    "ERROR: Aborting auto-link: Duplicate of Output '{}' found",
+                                    output
                                 )
                             }
-                            let list: Vec<String> = msg.unwrap();
-
-                            for output in list.iter() {
-                                if let Some(..) = senders.insert(
-                                    output.clone(), animus.to_string() 
-                                ) {
-                                    return println!(
-                                        "ERROR: Aborting auto-link: Duplicate of Output '{}' found",
-                                        output
-                                    )
-                                }
-                            }
-                        },
-                        _ => {
-                            return println!(
-                                "ERROR: Unexpected Outcome violates protocol. \
-                                Check the version of `animusd-lib` you are using."
-                            )
                         }
+                    },
+                    _ => {
+                        crate::report_failure();
+                        return println!(
+                            "ERROR: Unexpected Outcome violates protocol. \
+                            Check the version of `animusd-lib` you are using."
+                        )
                     }
-
                 }
+
             }
         }
     }
 
+    // Returns tract_name -> (output animus_name, input animus_name, ReceiverInfo)
+    // for each pair of tracts that was linked.
     fn attempt_link_tracts(
         &self, 
         senders: &mut HashMap<String, String>, 
         receivers: &mut HashMap<String, (String, ReceiverInfo)>
-    ) -> &Self {
+    ) -> HashMap<String, (String, String, ReceiverInfo)> {
+
+        let mut linked = HashMap::new();
 
         for (tract_name, animus) in senders.clone().iter() {
-            if let Some((_, info)) = receivers.get(tract_name) {
+            if let Some((receiver, info)) = receivers.get(tract_name) {
                 let action = Action::LinkOutput(info.clone());
-                if let Err(e) = self.send_command(animus, action.clone()) {
-                    Self::animus_command_error(animus, e)
-                } else {
-                    receivers.remove(tract_name);
-                    senders.remove(tract_name);
+                // Wait for the report so it isn't mistaken for a later reply
+                match self.request(animus, action.clone()) {
+                    Err(e) => Self::animus_request_error(animus, action, e),
+
+                    // Failed links stay in the unlinked report
+                    Ok(report) if !matches!(report.outcome, Outcome::Success) => {
+                        let e = anyhow::anyhow!(
+                            "Failed to link '{}': {}", 
+                            tract_name, crate::output::outcome_text(report.outcome)
+                        );
+                        if crate::output::is_json() {
+                            crate::output::emit(crate::output::animus_error_json(animus, &e))
+                        } else {
+                            println!("ERROR: '{}': {}", animus, e)
+                        }
+                        crate::report_failure()
+                    },
+
+                    Ok(_) => {
+                        linked.insert(
+                            tract_name.clone(), 
+                            (animus.clone(), receiver.clone(), info.clone())
+                        );
+                        receivers.remove(tract_name);
+                        senders.remove(tract_name);
+                    },
                 }
             }
         }
 
-        self
+        linked
+    }
+
+    fn report_autolink(
+        group: &str,
+        linked: HashMap<String, (String, String, ReceiverInfo)>,
+        senders: HashMap<String, String>, 
+        receivers: HashMap<String, (String, ReceiverInfo)>
+    ) {
+
+        if crate::output::is_json() {
+            let linked: Vec<_> = linked.iter()
+                .map(|(tract, (output, input, info))| json!({
+                    "tract": tract,
+                    "output_animus": output,
+                    "input_animus": input,
+                    "address": info.address.to_string(),
+                }))
+                .collect();
+            let unlinked_outputs: Vec<_> = senders.iter()
+                .map(|(tract, animus)| json!({ "tract": tract, "animus": animus }))
+                .collect();
+            let unlinked_inputs: Vec<_> = receivers.iter()
+                .map(|(tract, (animus, _))| json!({ "tract": tract, "animus": animus }))
+                .collect();
+
+            return crate::output::emit(json!({
+                "group": group,
+                "linked": linked,
+                "unlinked_outputs": unlinked_outputs,
+                "unlinked_inputs": unlinked_inputs,
+            }))
+        }
+
+        Self::report_unlinked_tracts(senders, receivers)
     }
 
     fn report_unlinked_tracts(
-        &self, 
         senders: HashMap<String, String>, 
         receivers: HashMap<String, (String, ReceiverInfo)>
     ) {
@@ -239,4 +299,3 @@ impl crate::Brainstorm {
     }
 }
 
-
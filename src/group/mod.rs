@@ -3,3 +3,5 @@ mod repl;
 mod helpers;
 mod error;
 
+pub(crate) use repl::{ GroupCli, GroupCommand };
+
@@ -12,13 +12,13 @@ use animusd_lib::protocol::Action;
     about = "REPL for managing Animi when grouped in a System",
     long_about = "This sub-REPL is used to manage a group (System) of Animi",
 )]
-struct GroupCli {
+pub(crate) struct GroupCli {
     #[command(subcommand)]
-    command: GroupCommand,
+    pub(crate) command: GroupCommand,
 }
 
 #[derive(Subcommand, Debug)]
-enum GroupCommand {
+pub(crate) enum GroupCommand {
 
     /// List all Animi that are members of this group.
     ListMembers,
@@ -78,6 +78,7 @@ impl crate::Brainstorm {
             },
             Err(e) => {
                 eprintln!("An error occurred while reading groups dir: {}", e);
+                crate::report_failure();
                 return
             }
         }
@@ -109,20 +110,8 @@ impl crate::Brainstorm {
         // TODO Update to use same pattern as meta
         loop { match repl.read_command() {
             ReadCommandOutput::Command(cli) => match cli.command {
-                
                 GroupCommand::Back => { break },
-
-                GroupCommand::ListMembers => Self::list_group_members(group),
-
-                GroupCommand::Wake => self.group_action(group, Action::Wake),
-                GroupCommand::Sleep => self.group_action(group, Action::Sleep),
-                GroupCommand::Status => self.group_action(group, Action::Status),
-                GroupCommand::Query => self.group_action(group, Action::Query),
-
-                GroupCommand::AutoLink => self.group_attempt_autolink(group),
-
-                GroupCommand::Add { animus } => Self::group_add_animus(group, &animus),
-                GroupCommand::Remove { animus } => Self::group_remove_animus(group, &animus),
+                command => self.execute_group_command(group, command),
             },
 
             ReadCommandOutput::EmptyLine => {/* Continue */},
@@ -134,25 +123,76 @@ impl crate::Brainstorm {
         }}
     }
 
+    // Execute a single group command, from the REPL or the command line.
+    pub(crate) fn execute_group_command(&self, group: &str, command: GroupCommand) {
+        match command {
+
+            GroupCommand::Back => {/* Handled by the REPL loop */},
+
+            GroupCommand::ListMembers => Self::list_group_members(group),
+
+            GroupCommand::Wake => self.group_action(group, Action::Wake),
+            GroupCommand::Sleep => self.group_action(group, Action::Sleep),
+            GroupCommand::Status => self.group_action(group, Action::Status),
+            GroupCommand::Query => self.group_action(group, Action::Query),
+
+            GroupCommand::AutoLink => self.group_attempt_autolink(group),
+
+            GroupCommand::Add { animus } => Self::group_add_animus(group, &animus),
+            GroupCommand::Remove { animus } => Self::group_remove_animus(group, &animus),
+        }
+    }
+
     // Send an animus command to each animus in the group.
     fn group_action(&self, group: &str, action: Action) {
 
         let read = crate::file::groups::read_group_members(group);
         if let Err(e) = &read {
+            crate::report_failure();
             return println!("Group file for '{}' is corrupted or missing: {}", group, e)
         }
 
         let members = read.unwrap();
+
+        if crate::output::is_json() {
+            let reports: Vec<_> = members.iter()
+                .map(|animus| self.group_member_json(animus, action.clone()))
+                .collect();
+
+            return crate::output::emit(serde_json::json!({
+                "group": group,
+                "action": action.to_string(),
+                "members": reports,
+            }))
+        }
+
         for animus in members.iter() {
             self.handle_command(animus, action.clone())
         }
     }
 
+    // Send an animus command and represent the response as JSON.
+    fn group_member_json(&self, animus: &str, action: Action) -> serde_json::Value {
+
+        match self.request(animus, action) {
+            Ok(report) => crate::output::report_json(&report),
+            Err(e) => {
+                crate::report_failure();
+                crate::output::animus_error_json(animus, &e.into())
+            }
+        }
+    }
+
     //
     fn list_group_members(group: &str) {
         match crate::file::groups::read_group_members(group) {
-            Err(e) => eprintln!("{}", e),
-            Ok(list) => {
+            Err(e) => {
+                eprintln!("{}", e);
+                crate::report_failure()
+            },
+            Ok(list) => if crate::output::is_json() {
+                crate::output::emit(serde_json::json!({ "group": group, "members": list }))
+            } else {
                 for member in list {
                     println!("{}", member)
                 }
@@ -164,13 +204,15 @@ impl crate::Brainstorm {
     fn group_add_animus(group: &str, animus: &str) {
         // TODO if animus doesn't exist, err
         if let Err(e) = crate::file::groups::group_add_animus(group, &animus) {
-            eprintln!("{}", e)
+            eprintln!("{}", e);
+            crate::report_failure()
         }
     }
 
     fn group_remove_animus(group: &str, animus: &str) {
         if let Err(e) = crate::file::groups::group_remove_animus(group, &animus) {
-            eprintln!("{}", e)
+            eprintln!("{}", e);
+            crate::report_failure()
         }
     }
 
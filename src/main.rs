@@ -1,5 +1,6 @@
 
 mod file;
+mod output;
 
 mod meta;
 mod animus;
@@ -7,25 +8,85 @@ mod group;
 
 use clap::Parser;
 
+// Default port on which animi listen for commands.
+pub(crate) const ANIMUS_PORT: u16 = 4048;
+
+// Default port on which the REPL receives reports.
+pub(crate) const REPORT_PORT: u16 = 4048;
+
+// Set when any command reports an error, to determine the exit status.
+static COMMAND_FAILED: std::sync::atomic::AtomicBool = 
+    std::sync::atomic::AtomicBool::new(false);
+
+// Record that a command has failed.
+pub(crate) fn report_failure() {
+    COMMAND_FAILED.store(true, std::sync::atomic::Ordering::Relaxed)
+}
+
+// Check whether a command has failed since the last check, and reset.
+pub(crate) fn take_failure() -> bool {
+    COMMAND_FAILED.swap(false, std::sync::atomic::Ordering::Relaxed)
+}
+
 // Singleton handling global program resources
 pub(crate) struct Brainstorm {
     socket: std::net::UdpSocket,
+    config: file::config::BrainstormConfig,
+    // Unregistered animi that have answered a Query, by name
+    discovered: std::cell::RefCell<std::collections::HashMap<String, std::net::SocketAddr>>,
 } 
 
 impl Brainstorm {
-    fn new() -> anyhow::Result<Self> {
+    fn new(config: file::config::BrainstormConfig) -> anyhow::Result<Self> {
+
+        let port = config.port.unwrap_or(REPORT_PORT);
+        let addr = std::net::SocketAddr::new(config.bind, port);
+        let socket = std::net::UdpSocket::bind(addr)?;
+        socket.set_read_timeout(Some(config.timeout))?;
 
-        Ok(Brainstorm {
-            socket: std::net::UdpSocket::bind("127.0.0.1:4048")?,
-        })
+        Ok(Brainstorm { socket, config, discovered: Default::default() })
     }
 }
 
-/// Run `$ brainstorm` to launch the control REPL.
+/// Run `$ brainstorm` to launch the control REPL,
+/// or follow it with a command to execute it once and exit.
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
 
+    /// Execute a single command without launching the REPL,
+    /// e.g., `brainstorm animus my_network wake`.
+    #[command(subcommand)]
+    command: Option<meta::MetaCommand>,
+
+    /// Display results as plain text or as one JSON object per line.
+    #[arg(long, value_enum, default_value_t = output::OutputFormat::Text)]
+    output: output::OutputFormat,
+
+    /// Override the address that Brainstorm receives reports on.
+    #[arg(long, value_name = "IP")]
+    bind: Option<std::net::IpAddr>,
+
+    /// Override the port that Brainstorm receives reports on.
+    #[arg(long, value_name = "PORT")]
+    port: Option<u16>,
+
+    /// Override the time to wait for a report, in milliseconds.
+    #[arg(long, value_name = "MS")]
+    timeout: Option<u64>,
+
+    /// Override the number of retries for commands that receive no report.
+    #[arg(long, value_name = "N")]
+    retries: Option<u32>,
+
+    /// Execute each line of a script file as if it was entered in the REPL.
+    #[arg(long, value_name = "FILE")]
+    script: Option<std::path::PathBuf>,
+
+    /// Add this to --script to stop at the first line that fails.
+    #[arg(long, action)]
+    stop_on_error: bool,
+
     /// Run with this flag to set up the necessary directories.
     #[arg(long, short, action)]
     setup: bool,
@@ -39,12 +100,13 @@ struct Args {
 fn main() {
 
     let args = Args::parse();
+    output::set_format(args.output);
 
     if args.setup == true {
 
         if let Err(e) = file::setup::directory_setup() {
             println!("Error creating framework directory: {}", e);
-            return
+            std::process::exit(1)
         }
 
         println!("Cajal setup complete");
@@ -52,15 +114,50 @@ fn main() {
         if args.run == false { return }
     }
 
+    // Only one-shot commands report failure through the exit status
+    let one_shot = args.command.is_some() || args.script.is_some();
+
     if file::setup::setup_ok() {
 
-        match Brainstorm::new() {
-            Ok(brainstorm) => brainstorm.meta_manager(),
-            Err(e) => eprintln!("{}", e),
+        let config = match file::config::read_brainstorm_config() {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Error reading Brainstorm config: {}", e);
+                std::process::exit(1)
+            }
+        };
+
+        let config = file::config::BrainstormConfig {
+            bind: args.bind.unwrap_or(config.bind),
+            // Animi reply to the port a command came from, so one-shot commands 
+            // and daemons can use any free port, leaving the default to the REPL
+            port: args.port.or(config.port).or(one_shot.then_some(0)),
+            timeout: args.timeout
+                .map(std::time::Duration::from_millis)
+                .unwrap_or(config.timeout),
+            retries: args.retries.unwrap_or(config.retries),
+            ..config
+        };
+
+        match Brainstorm::new(config) {
+            Ok(brainstorm) => match (args.script, args.command) {
+                (Some(script), _) => brainstorm.run_script(&script, args.stop_on_error),
+                (None, Some(command)) => brainstorm.execute_meta_command(command),
+                (None, None) => brainstorm.meta_manager(),
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                report_failure()
+            },
         }
 
     } else {
         println!("Missing `.cajal` directories. Run `$ brainstorm --setup`.");
+        report_failure()
+    }
+
+    if one_shot && COMMAND_FAILED.load(std::sync::atomic::Ordering::Relaxed) {
+        std::process::exit(1)
     }
 
 }
@@ -0,0 +1,24 @@
+// Identifies the step of building an animus executable that failed,
+// along with the cause or what the failed program wrote to stderr.
+#[derive(Debug)]
+pub(crate) enum BuildError {
+    Spawn(&'static str, std::io::Error),
+    Install(String),
+    Cache(std::io::Error),
+    Link(std::io::Error),
+    Permissions(String),
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::Spawn(program, e) => write!(f, "Could not run `{}`: {}", program, e),
+            BuildError::Install(stderr) => write!(f, "`cargo install animusd` failed:\n{}", stderr),
+            BuildError::Cache(e) => write!(f, "Could not cache the animusd executable: {}", e),
+            BuildError::Link(e) => write!(f, "Could not place the animusd executable: {}", e),
+            BuildError::Permissions(stderr) => write!(f, "Could not make the animusd executable runnable:\n{}", stderr),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
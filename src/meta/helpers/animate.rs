@@ -1,145 +1,267 @@
 
+use std::io::{ IsTerminal, Write };
+use std::path::Path;
+
+use crate::file::service::BuildOptions;
+
+use super::process::animus_pids;
+
+// Choose the name of a new animus, and what to do if it is taken.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct NamingArgs {
+    #[arg(long, help = 
+        "Name the Animus, instead of using the name of the network."
+    )]
+    name: Option<String>,
+
+    #[arg(long, short, action, help = 
+        "Use the name without asking for confirmation."
+    )]
+    yes: bool,
+
+    #[arg(long, action, conflicts_with = "fail_if_exists", help = 
+        "Replace an inactive local Animus that already has the name."
+    )]
+    overwrite: bool,
+
+    #[arg(long, action, help = 
+        "Fail instead of asking for another name if the name is taken."
+    )]
+    fail_if_exists: bool,
+}
+
+// Whether a proposed animus name can be used.
+enum NameCheck {
+    Available,
+    // A local animus has the name, and may be replaced
+    Taken(String),
+    Unusable(String),
+}
+
 impl crate::Brainstorm {
 
-    // Create a new animus setup for the given network.
+    // Create a new animus setup for the given network,
+    // either saved in ~/.cajal/saved or at a path to a `.nn` file.
     pub(crate) fn animate_network(
         &self, 
-        network_name: &str,
+        network: &Path,
+        naming: NamingArgs,
+        options: BuildOptions,
     ) -> anyhow::Result<()> {
 
-        if crate::file::saved::network_exists(network_name)? {
-            let animus_name = self.animus_setup(network_name)?;
-            println!("{} was animated as {}", network_name,  animus_name);
+        let (network_name, network_path) = crate::file::saved::resolve_network(network);
+
+        if ! network_path.is_file() {
+            return Err(anyhow::anyhow!(
+                "Network '{}' not found: No networks were animated. \n\
+                (Tip: Use `list-networks` to view saved networks)",
+                network.display()
+            ))
+        }
+
+        // Don't spend a build on a file that isn't a network
+        crate::file::saved::read_network(&network_path)?;
+
+        let animus = self.animus_setup(&network_name, &network_path, naming, options)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(serde_json::json!({
+                "command": "animate",
+                "network": network_name,
+                "animus": animus,
+            }))
         } else {
-            println!("Network '{}' not found!", network_name);
+            match animus {
+                Some(animus_name) => println!("{} was animated as {}", network_name,  animus_name),
+                None => println!("No animus was created"),
+            }
         }
         
         Ok(())
     }
 
     // Set up a new animus directory, executable, and all necessary files.
-    fn animus_setup(&self, network_name: &str) -> anyhow::Result<String> {
-
-        let network_path = crate::file::saved::network_path(network_name);
-        let network_path = std::path::Path::new(&network_path);
-
-        if ! network_path.exists() {
-            println!(
-                "Network '{}' not found: No networks were animated. \n\
-                (Tip: Use `list-networks` to view saved networks)",
-                network_name
-            )
-        }
+    fn animus_setup(
+        &self,
+        network_name: &str,
+        network_path: &Path,
+        naming: NamingArgs,
+        options: BuildOptions,
+    ) -> anyhow::Result<Option<String>> {
 
         // Rename the animus if necessary or desired
-        let animus_name = self.rename_animus(network_name.to_string())?;
+        let Some(animus_name) = self.choose_animus_name(network_name, &naming)? else {
+            return Ok(None)
+        };
 
-        if animus_name.is_none() {
-            // TODO return Ok(None)
+        // An animus being overwritten is set aside until the new one is built
+        let animus_dir = crate::file::animi::local_animus_path(&animus_name);
+        let replaced_dir = crate::file::animi::replaced_animus_path(&animus_name);
+        let replacing = Path::new(&animus_dir).is_dir();
+        if replacing {
+            // A copy left by an interrupted overwrite is older than the animus it replaced
+            if Path::new(&replaced_dir).exists() {
+                std::fs::remove_dir_all(&replaced_dir)?
+            }
+            if let Some(parent) = Path::new(&replaced_dir).parent() {
+                std::fs::create_dir_all(parent)?
+            }
+            std::fs::rename(&animus_dir, &replaced_dir)?
         }
 
-        // Safe because we check for it above
-        let animus_name = animus_name.expect("Animus name exists");
-
         // Create animus directory
-        let animus_dir = crate::file::animi::local_animus_path(&animus_name);
-        let animus_path = std::path::Path::new(&animus_dir);
-        if !animus_path.is_dir() {
-            std::fs::create_dir(animus_path)?
+        std::fs::create_dir(&animus_dir)?;
+
+        // The animus gets its own copy of the network, which it saves over
+        let network_copy = format!("{}/bin/{}.nn", animus_dir, network_name);
+        let built = std::fs::create_dir_all(format!("{}/bin", animus_dir))
+            .and_then(|_| std::fs::copy(network_path, &network_copy))
+            .map_err(anyhow::Error::from)
+            // Keep the build options so the animus can be rebuilt the same way
+            .and_then(|_| crate::file::service::write_build_options(&animus_name, &options))
+            .and_then(|_| {
+                Self::build_animus(&animus_name, &options).map_err(anyhow::Error::from)
+            });
+
+        if let Err(e) = built {
+            // Don't leave a broken animus behind
+            std::fs::remove_dir_all(&animus_dir)?;
+            if replacing {
+                std::fs::rename(&replaced_dir, &animus_dir)?
+            }
+            return Err(e)
         }
 
-        Self::build_animus(&animus_name)?;
+        if replacing {
+            std::fs::remove_dir_all(&replaced_dir)?
+        }
 
-        Ok(animus_name)
+        Ok(Some(animus_name))
     }
 
-    // Check if the proposed animus name is valid, then rename it if necessary.
-    fn rename_animus(&self, name: String) -> anyhow::Result<Option<String>> {
+    // Settle on a name for a new animus, asking for another if it can't be used.
+    // Without a terminal, or with `--yes` or `--fail-if-exists`, 
+    // problems are errors instead of prompts.
+    // Returns `None` if the user cancels.
+    fn choose_animus_name(
+        &self,
+        network_name: &str,
+        naming: &NamingArgs,
+    ) -> anyhow::Result<Option<String>> {
+
+        let interactive = !naming.yes && std::io::stdin().is_terminal();
+        let mut name = naming.name.clone()
+            .unwrap_or_else(|| network_name.to_string());
+
+        loop {
+            match self.check_animus_name(&name, naming)? {
+                NameCheck::Available => {
+                    if !interactive { return Ok(Some(name)) }
+
+                    let question = format!("Name '{}' is valid. Use? (Y/n): ", &name);
+                    if confirm(&question, true)? { return Ok(Some(name)) }
+                },
+                NameCheck::Taken(problem) => {
+                    if naming.fail_if_exists { return Err(anyhow::anyhow!(problem)) }
+                    if !interactive {
+                        return Err(anyhow::anyhow!(
+                            "{} (Use --overwrite to replace it)", problem
+                        ))
+                    }
+
+                    println!("{}", problem);
+                    if confirm("Overwrite it? (y/N): ", false)? { return Ok(Some(name)) }
+                },
+                NameCheck::Unusable(problem) => {
+                    if !interactive || naming.fail_if_exists {
+                        return Err(anyhow::anyhow!(problem))
+                    }
+                    println!("{}", problem);
+                },
+            }
 
-        // If the animus name is blank, exit without choosing a name
-        if name.is_empty() {
-            println!("No name chosen.");
-            return Ok(None)
+            println!("Type a new name or submit an empty line to cancel.");
+            match prompt("New name: ")? {
+                Some(new_name) => name = new_name,
+                None => {
+                    println!("No name chosen.");
+                    return Ok(None)
+                },
+            }
         }
+    }
 
-        let mut required = false;
+    // Check a proposed name against the naming rules and existing animi.
+    fn check_animus_name(&self, name: &str, naming: &NamingArgs) -> anyhow::Result<NameCheck> {
 
-        if ! crate::file::animi::valid_animus_name(&name) {
-            println!("Name '{}' is invalid! (Use a-Z, 0-9, and _)", &name);
-            required = true
+        if ! crate::file::animi::valid_animus_name(name) {
+            return Ok(NameCheck::Unusable(format!(
+                "Name '{}' is invalid! (Use a-Z, 0-9, and _)", name
+            )))
         }
 
-        if self.is_active(&name)? {
-            println!("An animus named '{}' is already active!", &name);
-            required = true
-        } 
+        if self.is_active(name)? {
+            return Ok(NameCheck::Unusable(format!(
+                "An animus named '{}' is already active!", name
+            )))
+        }
 
-        // Ask if rename desired 
-        if ! required { loop {
-            print!("Name '{}' is valid. Use? (Y/n): ", &name);
+        if crate::file::animi::remote_animus_exists(name)? {
+            return Ok(NameCheck::Unusable(format!(
+                "A remote animus is registered as '{}'", name
+            )))
+        }
 
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            let input = input.trim();
+        if crate::file::animi::local_animus_exists(name)? {
 
-            match input {
-                "Y" | "y" | "" => { return Ok(Some(name)) },
-                     "N" | "n" => { break },
-                             _ => {/* Continue loop */},
+            // A hung animus may not answer, but still runs from the directory
+            let pids = animus_pids(name)?;
+            if ! pids.is_empty() {
+                return Ok(NameCheck::Unusable(format!(
+                    "Animus '{}' is still running (PID {:?}). Use `kill {}` to stop it.", 
+                    name, pids, name
+                )))
             }
-        }}
-
-        println!("Type a new name or sumbit an empty line to cancel.");
-        print!("New name: ");
 
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input)?;
-        let new_name = input.trim().to_string();
+            if naming.overwrite { return Ok(NameCheck::Available) }
+            return Ok(NameCheck::Taken(format!(
+                "An animus named '{}' already exists on this device", name
+            )))
+        }
 
-        self.rename_animus(new_name)
+        Ok(NameCheck::Available)
     }
+}
 
-    // Install a unique `animusd` executable for an animus, based on features
-    fn build_animus(animus_name: &str) -> anyhow::Result<()> {
+// Ask a yes or no question, using the default for an empty answer.
+// End of input counts as "no".
+pub(super) fn confirm(question: &str, default: bool) -> anyhow::Result<bool> {
+    loop {
+        print!("{}", question);
+        std::io::stdout().flush()?;
 
-        // Build the animusd executable with the features specified.
-        let animus_dir = crate::file::animi::local_animus_path(&animus_name);
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input)? == 0 { return Ok(false) }
 
-        let mut cmd = std::process::Command::new("cargo");
-        cmd.arg("install")
-            .arg("animusd")
-            // TODO Logging enabled, and other features
-            //.arg("--features=logging")
-            .arg("--root")
-            .arg(&animus_dir);
-
-        let result = cmd.output()?;
-        if ! result.status.success() {
-            // TODO return Err
+        match input.trim() {
+            "Y" | "y" => return Ok(true),
+            "N" | "n" => return Ok(false),
+                   "" => return Ok(default),
+                    _ => {/* Continue loop */},
         }
+    }
+}
 
-        // Rename the service executable to distinguish it as a process
-        let default_path = format!("{}/bin/animusd", &animus_dir);
-        let bin_path = format!("{}-{}", &default_path, &animus_name);
-
-        let mut cmd = std::process::Command::new("mv");
-        cmd.arg(&default_path).arg(&bin_path);
-
-        let result = cmd.output()?;
-        if ! result.status.success() {
-            // TODO return Err
-        }
+// Read a line of input, or `None` if it is empty.
+fn prompt(message: &str) -> anyhow::Result<Option<String>> {
 
-        // Make animusd executable
-        let mut cmd = std::process::Command::new("chmod");
-        cmd.arg("+x").arg(&bin_path);
+    print!("{}", message);
+    std::io::stdout().flush()?;
 
-        let result = cmd.output()?;
-        if ! result.status.success() {
-            // TODO return Err
-        }
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_string();
 
-        Ok(())
-    }
+    Ok((!input.is_empty()).then_some(input))
 }
 
@@ -0,0 +1,337 @@
+
+//! Helper functions for building the animusd executable for an animus.
+
+use std::io::{ BufRead, Write };
+use std::process::{ Command, Stdio };
+
+use crate::file::service::BuildOptions;
+use crate::meta::error::BuildError;
+
+// Number of lines of cargo output kept to explain a failed build.
+const KEPT_OUTPUT: usize = 30;
+
+// Choose how animusd is built, when animating or upgrading an animus.
+#[derive(clap::Args, Debug, Clone)]
+pub(crate) struct BuildArgs {
+    #[arg(long, value_delimiter = ',', help = 
+        "Enable animusd cargo features, e.g., `--features logging`."
+    )]
+    features: Vec<String>,
+
+    #[arg(long, conflicts_with_all = ["path", "git"], help = 
+        "Install this version of animusd from crates.io, e.g., 0.3.1"
+    )]
+    version: Option<String>,
+
+    #[arg(long, conflicts_with = "git", help = 
+        "Install animusd from a local source directory instead of crates.io."
+    )]
+    path: Option<std::path::PathBuf>,
+
+    #[arg(long, help = 
+        "Install animusd from a git repository instead of crates.io."
+    )]
+    git: Option<String>,
+
+    #[arg(long, conflicts_with_all = ["version", "path", "git", "features"], help = 
+        "Use a prebuilt animusd executable instead of building one."
+    )]
+    binary: Option<std::path::PathBuf>,
+
+    #[arg(long, action, conflicts_with_all = ["version", "path", "git", "binary"], help = 
+        "Install the latest release of animusd, instead of the newest one in the build cache."
+    )]
+    latest: bool,
+}
+
+impl BuildArgs {
+
+    // Apply these arguments over the options an animus was built with.
+    // A new source replaces the old one, and features are kept unless given.
+    // Without a source, animusd is installed from crates.io.
+    pub(crate) fn apply(self, mut options: BuildOptions) -> anyhow::Result<BuildOptions> {
+
+        if !self.features.is_empty() {
+            options.features = self.features
+        }
+
+        options.binary = None;
+        options.latest = self.latest;
+
+        if let Some(binary) = self.binary {
+            options.binary = Some(canonical(&binary)?);
+            options.path = None;
+            options.git = None;
+            options.version = None;
+        } else if let Some(path) = self.path {
+            options.path = Some(canonical(&path)?);
+            options.git = None;
+            options.version = None;
+        } else if let Some(git) = self.git {
+            options.git = Some(git);
+            options.path = None;
+            options.version = None;
+        } else {
+            options.version = self.version;
+            options.path = None;
+            options.git = None;
+        }
+
+        Ok(options)
+    }
+}
+
+// Resolve a path given on the command line,
+// since it is kept in `service.cfg` and used again from any directory.
+fn canonical(path: &std::path::Path) -> anyhow::Result<String> {
+    let resolved = std::fs::canonicalize(path)
+        .map_err(|e| anyhow::anyhow!("Can't find '{}': {}", path.display(), e))?;
+    Ok(resolved.display().to_string())
+}
+
+impl crate::Brainstorm {
+
+    // Build the executable for an existing animus again,
+    // e.g., after a failed build or to pick up a new animusd release.
+    pub(crate) fn rebuild_animus(&self, animus_name: &str) -> anyhow::Result<()> {
+
+        self.check_rebuildable(animus_name)?;
+
+        let options = crate::file::service::read_build_options(animus_name)?;
+        Self::build_animus(animus_name, &options)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(serde_json::json!({
+                "command": "rebuild",
+                "animus": animus_name,
+                "source": options.source(),
+            }))
+        } else {
+            println!("Animus '{}' was rebuilt", animus_name)
+        }
+
+        Ok(())
+    }
+
+    // Rebuild an animus with a different animusd version, source or features.
+    // Config and network files are left as they are.
+    pub(crate) fn upgrade_animus(&self, animus_name: &str, args: BuildArgs) -> anyhow::Result<()> {
+
+        self.check_rebuildable(animus_name)?;
+
+        let previous = crate::file::service::read_build_options(animus_name)?;
+        let mut options = args.apply(previous.clone())?;
+
+        // Upgrading without a version installs the latest release, not a cached one
+        if options.binary.is_none() && options.version.is_none() 
+            && options.path.is_none() && options.git.is_none() 
+        {
+            options.latest = true
+        }
+        crate::file::service::write_build_options(animus_name, &options)?;
+
+        // The old executable is only replaced once the build succeeds
+        if let Err(e) = Self::build_animus(animus_name, &options) {
+            crate::file::service::write_build_options(animus_name, &previous)?;
+            return Err(e.into())
+        }
+
+        if crate::output::is_json() {
+            crate::output::emit(serde_json::json!({
+                "command": "upgrade",
+                "animus": animus_name,
+                "source": options.source(),
+            }))
+        } else {
+            println!("Animus '{}' was upgraded to animusd {}", animus_name, options.source())
+        }
+
+        Ok(())
+    }
+
+    // An animus can only be rebuilt while it is not running.
+    fn check_rebuildable(&self, animus_name: &str) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus_name)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus_name))
+        }
+
+        if self.is_active(animus_name)? {
+            return Err(anyhow::anyhow!(
+                "'{}' is active. Terminate it before rebuilding.", animus_name
+            ))
+        }
+
+        Ok(())
+    }
+
+    // Install a unique `animusd` executable for an animus, based on features.
+    // Releases from crates.io are built once and shared through the build cache.
+    // A failed build leaves the animus directory for the caller to clean up.
+    pub(crate) fn build_animus(animus_name: &str, options: &BuildOptions) -> Result<(), BuildError> {
+
+        let animus_dir = crate::file::animi::local_animus_path(&animus_name);
+        let bin_path = format!("{}/bin/animusd-{}", &animus_dir, &animus_name);
+        let staging = crate::file::cache::staging_dir(animus_name);
+
+        // A prebuilt executable belongs to the user, so it is copied rather than linked
+        let link = options.binary.is_none();
+        let result = Self::find_or_build_animusd(options, &staging)
+            .and_then(|executable| place_executable(&executable, &bin_path, link));
+
+        // Nothing in the staging root is needed once the executable is in place
+        let _ = std::fs::remove_dir_all(&staging);
+
+        result
+    }
+
+    // Get the path of an executable matching the options,
+    // from the user, from the cache, or by building one.
+    fn find_or_build_animusd(options: &BuildOptions, staging: &str) -> Result<String, BuildError> {
+
+        if let Some(binary) = &options.binary {
+            return Ok(binary.clone())
+        }
+
+        if let Some(cached) = crate::file::cache::find_cached_animusd(options) {
+            if ! crate::output::is_json() {
+                println!("Using cached {}", cached)
+            }
+            return Ok(cached)
+        }
+
+        // Build the animusd executable with the features specified.
+        let mut cmd = Command::new("cargo");
+        cmd.arg("install");
+
+        match (&options.path, &options.git) {
+            (Some(path), _) => { cmd.arg("--path").arg(path); },
+            (None, Some(git)) => { cmd.arg("--git").arg(git).arg("animusd"); },
+            (None, None) => {
+                cmd.arg("animusd");
+                if let Some(version) = &options.version {
+                    cmd.arg("--version").arg(version);
+                }
+            },
+        }
+
+        if !options.features.is_empty() {
+            cmd.arg("--features").arg(options.features.join(","));
+        }
+
+        cmd.arg("--root").arg(staging);
+
+        let installed = cargo_with_progress(cmd)?;
+        let built = format!("{}/bin/animusd", staging);
+
+        // Other sources can change without changing version, so only releases are cached
+        if options.path.is_some() || options.git.is_some() { return Ok(built) }
+        let Some(version) = installed.or(options.version.clone()) else { return Ok(built) };
+
+        let cached = crate::file::cache::cached_animusd_path(&version, &options.features);
+        std::fs::create_dir_all(crate::file::cache::cache_dir()).map_err(BuildError::Cache)?;
+        std::fs::rename(&built, &cached).map_err(BuildError::Cache)?;
+
+        Ok(cached)
+    }
+}
+
+// Put an executable in place as `bin/animusd-<name>`, which distinguishes it as a process.
+// Executables that Brainstorm owns can be hard linked, but others are always copied,
+// since making the link executable would change the original.
+pub(super) fn place_executable(
+    executable: &str, 
+    bin_path: &str, 
+    link: bool,
+) -> Result<(), BuildError> {
+
+    if let Some(bin_dir) = std::path::Path::new(bin_path).parent() {
+        std::fs::create_dir_all(bin_dir).map_err(BuildError::Link)?;
+    }
+
+    if std::path::Path::new(bin_path).exists() {
+        std::fs::remove_file(bin_path).map_err(BuildError::Link)?;
+    }
+
+    // Hard links save space, but can't cross filesystems
+    if !link || std::fs::hard_link(executable, bin_path).is_err() {
+        std::fs::copy(executable, bin_path).map_err(BuildError::Link)?;
+    }
+
+    // Make animusd executable
+    let mut cmd = Command::new("chmod");
+    cmd.arg("+x").arg(bin_path);
+    run_step(cmd, "chmod", BuildError::Permissions)?;
+
+    Ok(())
+}
+
+// Run cargo, showing each crate as it compiles on a single progress line.
+// Returns the version of animusd that was installed, if cargo reported it.
+fn cargo_with_progress(mut cmd: Command) -> Result<Option<String>, BuildError> {
+
+    let show_progress = ! crate::output::is_json();
+    if show_progress {
+        println!("Building animusd. This may take a few minutes...")
+    }
+
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().map_err(|e| BuildError::Spawn("cargo", e))?;
+    let stderr = child.stderr.take().expect("Stderr is piped");
+
+    let mut output: Vec<String> = Vec::new();
+    let mut compiled = 0;
+    let mut installed = None;
+
+    for line in std::io::BufReader::new(stderr).lines() {
+        let line = line.map_err(|e| BuildError::Spawn("cargo", e))?;
+        let step = line.trim();
+
+        if step.starts_with("Compiling") { compiled += 1 }
+
+        // e.g., "Installing animusd v0.3.1"
+        if let Some(version) = step.strip_prefix("Installing animusd v") {
+            installed = version.split_whitespace().next().map(|v| v.to_string())
+        }
+
+        if show_progress && ["Updating", "Downloading", "Compiling", "Installing"]
+            .iter().any(|s| step.starts_with(s))
+        {
+            // Overwrite the previous progress line
+            print!("\r\x1b[K[{}] {}", compiled, step);
+            let _ = std::io::stdout().flush();
+        }
+
+        output.push(line);
+        if output.len() > KEPT_OUTPUT { output.remove(0); }
+    }
+
+    let status = child.wait().map_err(|e| BuildError::Spawn("cargo", e))?;
+    if show_progress { print!("\r\x1b[K") }
+
+    if ! status.success() {
+        return Err(BuildError::Install(output.join("\n")))
+    }
+
+    if show_progress { println!("Compiled {} crates", compiled) }
+    Ok(installed)
+}
+
+// Run a program, wrapping what it wrote to stderr in an error if it fails.
+fn run_step(
+    mut cmd: Command,
+    program: &'static str,
+    failed: fn(String) -> BuildError,
+) -> Result<(), BuildError> {
+
+    let result = cmd.output().map_err(|e| BuildError::Spawn(program, e))?;
+
+    if ! result.status.success() {
+        return Err(failed(String::from_utf8_lossy(&result.stderr).trim().to_string()))
+    }
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+
+use std::path::Path;
+
+use super::build::place_executable;
+use super::process::animus_pids;
+use super::schedule::retarget_schedule;
+use super::systemd::warn_stale_group_units;
+
+impl crate::Brainstorm {
+
+    // Copy a local animus under a new name, with its config and network as last saved.
+    // The new animus shares the executable of the original, but not its runtime files.
+    pub(crate) fn clone_animus(&self, animus: &str, new_name: &str) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus))
+        }
+        self.check_new_name(new_name)?;
+
+        if self.is_active(animus)? {
+            eprintln!("WARN: '{}' is active. Its network is copied as it was last saved.", animus)
+        }
+
+        let animus_dir = crate::file::animi::local_animus_path(animus);
+        let new_dir = crate::file::animi::local_animus_path(new_name);
+        let old_bin = format!("{}/bin/animusd-{}", animus_dir, animus);
+        let new_bin = format!("{}/bin/animusd-{}", new_dir, new_name);
+
+        let copied = copy_animus_dir(Path::new(&animus_dir), Path::new(&new_dir), &old_bin)
+            .and_then(|_| place_executable(&old_bin, &new_bin, true).map_err(anyhow::Error::from));
+
+        if let Err(e) = copied {
+            // Don't leave a partial copy behind
+            let _ = std::fs::remove_dir_all(&new_dir);
+            return Err(e)
+        }
+
+        if crate::output::is_json() {
+            crate::output::emit(serde_json::json!({
+                "command": "clone",
+                "animus": animus,
+                "new_name": new_name,
+            }))
+        } else {
+            println!("Animus '{}' was cloned as '{}'", animus, new_name)
+        }
+
+        Ok(())
+    }
+
+    // Rename a local animus, its executable, its membership in groups,
+    // and the schedule entries that target it.
+    // The animus must not be running.
+    pub(crate) fn rename_local_animus(&self, animus: &str, new_name: &str) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus))
+        }
+        self.check_new_name(new_name)?;
+
+        if self.is_active(animus)? || !animus_pids(animus)?.is_empty() {
+            return Err(anyhow::anyhow!(
+                "'{}' is running. Terminate it before renaming.", animus
+            ))
+        }
+
+        // The unit refers to the old paths, so it is removed rather than left broken
+        let unit = crate::file::systemd::animus_unit_name(animus);
+        let had_unit = crate::file::systemd::unit_exists(&unit);
+        if had_unit {
+            self.remove_units(animus, false)?
+        }
+
+        // A stale PID file would look like a crash to `supervise`
+        crate::file::run::remove_animus_pid(animus)?;
+
+        let animus_dir = crate::file::animi::local_animus_path(animus);
+        let new_dir = crate::file::animi::local_animus_path(new_name);
+        std::fs::rename(&animus_dir, &new_dir)?;
+
+        let old_bin = format!("{}/bin/animusd-{}", new_dir, animus);
+        if Path::new(&old_bin).exists() {
+            std::fs::rename(&old_bin, format!("{}/bin/animusd-{}", new_dir, new_name))?
+        }
+
+        let mut updated = Vec::new();
+        for group in crate::file::groups::group_names()? {
+            let members = crate::file::groups::read_group_members(&group)?;
+            if members.iter().any(|member| member == animus) {
+
+                let members = members.into_iter()
+                    .map(|member| if member == animus { new_name.to_string() } else { member })
+                    .collect();
+
+                crate::file::groups::write_group_members(&group, members)?;
+                updated.push(group)
+            }
+        }
+
+        let scheduled = retarget_schedule(animus, Some(new_name))?;
+        let stale_groups = warn_stale_group_units(animus)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(serde_json::json!({
+                "command": "rename",
+                "animus": animus,
+                "new_name": new_name,
+                "groups": updated,
+                "schedule_entries": scheduled,
+                "stale_group_units": stale_groups,
+                "unit_removed": had_unit,
+            }));
+            return Ok(())
+        }
+
+        for group in updated.iter() {
+            println!("Updated group '{}'", group)
+        }
+        if scheduled > 0 {
+            println!("Updated {} schedule entries", scheduled)
+        }
+
+        println!("Animus '{}' was renamed to '{}'", animus, new_name);
+        if had_unit {
+            println!("Use `service install {}` to replace its systemd unit", new_name)
+        }
+
+        Ok(())
+    }
+
+    // Check that a new name is valid and not used by any animus.
+    fn check_new_name(&self, new_name: &str) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::valid_animus_name(new_name) {
+            return Err(anyhow::anyhow!("Name '{}' is invalid! (Use a-Z, 0-9, and _)", new_name))
+        }
+
+        if crate::file::animi::animus_exists(new_name)? || self.is_active(new_name)? {
+            return Err(anyhow::anyhow!("An animus named '{}' already exists", new_name))
+        }
+
+        Ok(())
+    }
+}
+
+// Copy an animus directory, except for its executable, runtime files and snapshots.
+fn copy_animus_dir(from: &Path, to: &Path, executable: &str) -> anyhow::Result<()> {
+
+    std::fs::create_dir_all(to)?;
+
+    for entry in std::fs::read_dir(from)?.flatten() {
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+
+        if path == Path::new(executable) { continue }
+
+        if path.is_dir() {
+            // Runtime files and snapshots belong to the original
+            if ["run", "logs", "snapshots"].iter().any(|dir| entry.file_name() == *dir) {
+                continue
+            }
+            copy_animus_dir(&path, &target, executable)?
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+
+use std::io::IsTerminal;
+
+use serde_json::json;
+
+use super::animate::confirm;
+use super::process::animus_pids;
+use super::schedule::retarget_schedule;
+use super::systemd::warn_stale_group_units;
+
+impl crate::Brainstorm {
+
+    // Delete a local animus, its executable and records,
+    // optionally keeping an archive of its directory.
+    pub(crate) fn deanimate_animus(
+        &self,
+        animus: &str,
+        archive: bool,
+        yes: bool,
+    ) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus))
+        }
+
+        if self.is_active(animus)? || !animus_pids(animus)?.is_empty() {
+            return Err(anyhow::anyhow!(
+                "'{}' is running. Terminate it before deanimating.", animus
+            ))
+        }
+
+        let json = crate::output::is_json();
+
+        if !yes && std::io::stdin().is_terminal() {
+            let question = format!("Delete animus '{}'? (y/N): ", animus);
+            if ! confirm(&question, false)? {
+                if json {
+                    crate::output::emit(json!({
+                        "command": "deanimate",
+                        "animus": animus,
+                        "deanimated": false,
+                    }))
+                } else {
+                    println!("'{}' was not deanimated", animus)
+                }
+                return Ok(())
+            }
+        }
+
+        let archived = if archive {
+            let path = Self::archive_animus(animus)?;
+            if !json { println!("Archived '{}' to {}", animus, path) }
+            Some(path)
+        } else {
+            None
+        };
+
+        let groups = Self::remove_from_groups(animus)?;
+        if !json {
+            for group in groups.iter() {
+                println!("Removed '{}' from group '{}'", animus, group)
+            }
+        }
+
+        let unscheduled = retarget_schedule(animus, None)?;
+        if !json && unscheduled > 0 {
+            println!("Removed {} schedule entries for '{}'", unscheduled, animus)
+        }
+
+        let unit = crate::file::systemd::animus_unit_name(animus);
+        if crate::file::systemd::unit_exists(&unit) {
+            self.remove_units(animus, false)?
+        }
+        let stale_groups = warn_stale_group_units(animus)?;
+
+        std::fs::remove_dir_all(crate::file::animi::local_animus_path(animus))?;
+
+        if json {
+            crate::output::emit(json!({
+                "command": "deanimate",
+                "animus": animus,
+                "deanimated": true,
+                "archive": archived,
+                "groups": groups,
+                "schedule_entries": unscheduled,
+                "stale_group_units": stale_groups,
+            }))
+        } else {
+            println!("Animus '{}' was deanimated", animus)
+        }
+
+        Ok(())
+    }
+
+    // Pack the animus directory (config, logs and network) into a tarball,
+    // leaving out the executable, which can be rebuilt.
+    fn archive_animus(animus: &str) -> anyhow::Result<String> {
+
+        std::fs::create_dir_all(crate::file::archive::archive_dir())?;
+        let path = crate::file::archive::new_archive_path(animus)?;
+
+        let animus_dir = crate::file::animi::local_animus_path(animus);
+        let local = std::path::Path::new(&animus_dir).parent()
+            .expect("Animus directory is in `local`");
+
+        let mut cmd = std::process::Command::new("tar");
+        cmd.arg("-czf").arg(&path)
+            .arg("-C").arg(local)
+            .arg(format!("--exclude={}/bin/animusd-{}", animus, animus))
+            .arg(animus);
+
+        let result = cmd.output()?;
+        if ! result.status.success() {
+            let _ = std::fs::remove_file(&path);
+            return Err(anyhow::anyhow!(
+                "Failed to archive '{}': {}",
+                animus, String::from_utf8_lossy(&result.stderr).trim()
+            ))
+        }
+
+        Ok(path)
+    }
+
+    // Remove an animus from every group it belongs to,
+    // returning the names of those groups.
+    fn remove_from_groups(animus: &str) -> anyhow::Result<Vec<String>> {
+
+        let mut removed = Vec::new();
+
+        for group in crate::file::groups::group_names()? {
+            let members = crate::file::groups::read_group_members(&group)?;
+            if members.iter().any(|member| member == animus) {
+                crate::file::groups::group_remove_animus(&group, animus)?;
+                removed.push(group)
+            }
+        }
+
+        Ok(removed)
+    }
+}
@@ -1,28 +1,179 @@
 
-// Lists all animi
-pub(crate) fn active_animi() -> anyhow::Result<()> {
+use std::collections::BTreeMap;
+use std::net::{ Ipv4Addr, SocketAddr };
+use std::time::{ Duration, Instant };
 
-    // TODO Send Query AnimusCommand with no name, wait 1 sec to collect responses
-    // print list of all that respond
-    Ok(())
+use serde_json::json;
+
+use animusd_lib::protocol::{ Command, Action, Report, Outcome };
+
+
+impl crate::Brainstorm {
+
+    // Query every recorded animus (and optionally broadcast a nameless Query),
+    // then print a table of the animi that respond within the window.
+    pub(crate) fn active_animi(
+        &self,
+        window: Duration,
+        broadcast: bool,
+    ) -> anyhow::Result<()> {
+
+        let mut records = crate::file::animi::local_animus_names()?;
+        records.extend(crate::file::animi::remote_animus_names()?);
+
+        for animus in records.iter() {
+            if let Err(e) = self.send_command(animus, Action::Query) {
+                eprintln!("WARN: Could not query '{}': {}", animus, e)
+            }
+        }
+
+        if broadcast {
+            if let Err(e) = self.broadcast_query() {
+                eprintln!("WARN: Broadcast query was not sent: {}", e)
+            }
+        }
+
+        let responders = self.collect_query_reports(window)?;
+        for (animus, addr) in responders.iter() {
+            if ! crate::file::animi::animus_exists(animus)? {
+                self.record_discovered(animus, *addr)
+            }
+        }
+
+        let json = crate::output::is_json();
+        let mut active = Vec::new();
+
+        if responders.is_empty() && !json {
+            println!("No animi responded within {}ms", window.as_millis());
+            return Ok(())
+        }
+
+        if !json {
+            println!("{:<24} {:<24} {:<12} {}", "NAME", "ADDRESS", "VERSION", "STATE");
+        }
+
+        for (animus, addr) in responders.iter() {
+
+            let version = match self.request_at(animus, *addr, Action::Version, window) {
+                Ok(report) => crate::output::outcome_text(report.outcome),
+                Err(_) => "?".to_string(),
+            };
+
+            let state = match self.request_at(animus, *addr, Action::Status, window) {
+                Ok(report) => match report.outcome {
+                    Outcome::Success => "awake",
+                    _ => "asleep",
+                },
+                Err(_) => "?",
+            };
+
+            if json {
+                active.push(json!({
+                    "name": animus,
+                    "address": addr.to_string(),
+                    "version": version,
+                    "state": state,
+                }))
+            } else {
+                println!("{:<24} {:<24} {:<12} {}", animus, addr, version, state)
+            }
+        }
+
+        if json {
+            crate::output::emit(json!({ "active": active }))
+        }
+
+        Ok(())
+    }
+
+    // Send a Query with no name, to be answered by any animus that hears it.
+    fn broadcast_query(&self) -> anyhow::Result<()> {
+
+        if self.config.bind.is_ipv6() {
+            return Err(anyhow::anyhow!("Broadcast requires an IPv4 bind address"))
+        }
+
+        // A loopback socket can't reach the network, so nothing would answer
+        if self.config.bind.is_loopback() {
+            return Err(anyhow::anyhow!(
+                "Broadcast can't be sent from the loopback address {}. \
+                Bind to a network interface with --bind 0.0.0.0 or `bind` in brainstorm.cfg",
+                self.config.bind
+            ))
+        }
+
+        let command = Command::new("", Action::Query);
+        let broadcast = SocketAddr::new(Ipv4Addr::BROADCAST.into(), self.config.animus_port);
+
+        self.socket.set_broadcast(true)?;
+        self.socket.send_to(&command.encode()?, broadcast)?;
+
+        Ok(())
+    }
+
+    // Collect every successful Query report that arrives before the window closes.
+    // Returns animus name -> address of the responding socket.
+    fn collect_query_reports(
+        &self,
+        window: Duration
+    ) -> anyhow::Result<BTreeMap<String, SocketAddr>> {
+
+        let previous_timeout = self.socket.read_timeout()?;
+        let deadline = Instant::now() + window;
+        let mut responders = BTreeMap::new();
+
+        let mut buf = vec![0; crate::animus::MAX_DATAGRAM];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() { break }
+
+            self.socket.set_read_timeout(Some(remaining))?;
+            match self.socket.recv_from(&mut buf) {
+                Ok((len, addr)) => {
+                    // Ignore anything that is not a report to a Query
+                    if let Ok(report) = Report::decode(&buf[..len]) {
+                        if matches!(report.action, Action::Query)
+                            && matches!(report.outcome, Outcome::Success) {
+                            responders.insert(report.name, addr);
+                        }
+                    }
+                },
+                // Timed out
+                Err(_) => break,
+            }
+        }
+
+        self.socket.set_read_timeout(previous_timeout)?;
+        Ok(responders)
+    }
 }
 
 // Print a list of all files that appear in the `animi` directory.
-pub(crate) fn all_animi() -> anyhow::Result<()> {
+pub(crate) fn all_animi(default_port: u16) -> anyhow::Result<()> {
+
+    let local_animi = crate::file::animi::local_animus_names()?;
+    let remote_animi = crate::file::animi::remote_animus_names()?;
+
+    if crate::output::is_json() {
+        let remote: Vec<_> = remote_animi.iter()
+            .map(|name| json!({
+                "name": name,
+                "address": crate::file::remote::remote_animus_addr(name, default_port).ok()
+                    .map(|addr| addr.to_string()),
+            }))
+            .collect();
+
+        crate::output::emit(json!({ "local": local_animi, "remote": remote }));
+        return Ok(())
+    }
 
     println!("Local animi:");
-    let local_animi = crate::file::animi::read_local_animi()?.flatten();
-    for animus in local_animi {
-        let name = animus.file_name().into_string()
-            .map_err(|_| anyhow::anyhow!("Couldn't read OsString"))?;
+    for name in local_animi {
         println!("{}", name) 
     }
 
     println!("Remote animi:");
-    let remote_animi = crate::file::animi::read_remote_animi()?.flatten();
-    for animus in remote_animi {
-        let name = animus.file_name().into_string()
-            .map_err(|_| anyhow::anyhow!("Couldn't read OsString"))?;
+    for name in remote_animi {
         println!("{}", name) 
     }
 
@@ -32,15 +183,24 @@ pub(crate) fn all_animi() -> anyhow::Result<()> {
 // Print a list of all network files that appear in the `saved` directory.
 pub(crate) fn saved_networks() -> anyhow::Result<()> {
 
+    let mut networks = Vec::new();
+
     let saved = crate::file::saved::read_saved()?.flatten();
     for network in saved {
         let name = network.file_name().into_string()
             .map_err(|_| anyhow::anyhow!("Couldn't read OsString"))?;
         if name.ends_with(".nn") {
+            networks.push(name)
+        }
+    }
+
+    if crate::output::is_json() {
+        crate::output::emit(json!({ "networks": networks }))
+    } else {
+        for name in networks {
             println!("{}", name) 
         }
     }
 
     Ok(())
 }
-
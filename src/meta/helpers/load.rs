@@ -1,42 +1,123 @@
 
+use std::os::unix::process::CommandExt;
+use std::time::{ Duration, Instant };
+
+use animusd_lib::protocol::{ Action, Outcome };
+
+// How often to check whether a launched animus has begun answering.
+const LAUNCH_POLL: Duration = Duration::from_millis(200);
+
 impl crate::Brainstorm {
 
     // Load and activate an existing but inactive animus.
     pub(crate) fn load_animus(&self, animus_name: &str) -> anyhow::Result<()> {
 
-        if crate::file::animi::local_animus_exists(animus_name)? {
+        if !crate::file::animi::local_animus_exists(animus_name)? {
+            return Err(anyhow::anyhow!(
+                "Animus '{}' not found. Use `animate` to generate it", animus_name
+            ))
+        }
+
+        let json = crate::output::is_json();
 
-            if !self.is_active(animus_name)? {
-                Self::launch_animus(animus_name)?;
-                println!("Animus '{}' has loaded", animus_name)
+        if !self.is_active(animus_name)? {
+            let child = self.launch_animus(animus_name)?;
+            if json {
+                crate::output::emit(serde_json::json!({
+                    "command": "load",
+                    "animus": animus_name,
+                    "pid": child.id(),
+                }))
             } else {
-                println!("An animus named '{}' is already running", animus_name)
+                println!("Animus '{}' has loaded (PID {})", animus_name, child.id())
             }
-
+            reap_when_exited(animus_name, child)
+        } else if json {
+            crate::output::emit(serde_json::json!({
+                "command": "load",
+                "animus": animus_name,
+                "already_running": true,
+            }))
         } else {
-            println!("'{}' not found! Use `animate` to generate it", animus_name)
+            println!("An animus named '{}' is already running", animus_name)
         }
 
         Ok(())
     }
 
-    // Execute the animusd service for an animus.
-    fn launch_animus(animus_name: &str) -> anyhow::Result<()> {
+    // Execute the animusd service for an animus as a detached process,
+    // then wait for it to answer a Query.
+    pub(crate) fn launch_animus(&self, animus_name: &str) -> anyhow::Result<std::process::Child> {
 
         let animus_dir = crate::file::animi::local_animus_path(animus_name);
         let bin_path = format!("{}/bin/animusd-{}", animus_dir, animus_name);
-        
+
+        crate::file::run::rotate_animus_logs(animus_name)?;
+        let (out_path, err_path) = crate::file::run::animus_log_paths(animus_name);
+
         // The binary should have been made executable when it was set up.
         let mut cmd = std::process::Command::new(bin_path);
+        cmd.current_dir(&animus_dir)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::fs::File::create(&out_path)?)
+            .stderr(std::fs::File::create(&err_path)?)
+            // Leave the REPL's process group so the animus outlives it
+            .process_group(0);
+
+        let mut child = cmd.spawn()?;
+        let pid = child.id();
+        crate::file::run::write_animus_pid(animus_name, pid)?;
+
+        let addr = self.route(animus_name)?;
+        let deadline = Instant::now() + self.config.launch_timeout;
+
+        while Instant::now() < deadline {
 
-        let result = cmd.output()?;
-        if !result.status.success() {
-            // TODO Print error?
-            // TBD Returns on execeution or completion?
+            if let Some(status) = child.try_wait()? {
+                crate::file::run::remove_animus_pid(animus_name)?;
+                return Err(anyhow::anyhow!(
+                    "animusd-{} exited during launch ({})\n{}",
+                    animus_name, status, log_tail(&err_path)
+                ))
+            }
+
+            if let Ok(report) = self.request_at(animus_name, addr, Action::Query, LAUNCH_POLL) {
+                if matches!(report.outcome, Outcome::Success) { return Ok(child) }
+            }
         }
 
-        Ok(())
+        // Don't leave an unresponsive process running, or a zombie behind
+        let _ = child.kill();
+        let _ = child.wait();
+        crate::file::run::remove_animus_pid(animus_name)?;
+
+        Err(anyhow::anyhow!(
+            "animusd-{} (PID {}) did not answer within {}ms, so it was stopped. See {}\n{}",
+            animus_name, pid, self.config.launch_timeout.as_millis(),
+            err_path, log_tail(&err_path)
+        ))
     }
 
 }
 
+// Collect the exit status of a launched animus whenever it exits,
+// so a long-running REPL doesn't accumulate zombie processes,
+// and record it in the run directory for `supervise` to report.
+pub(super) fn reap_when_exited(animus: &str, mut child: std::process::Child) {
+    let animus = animus.to_string();
+    std::thread::spawn(move || {
+        if let Ok(status) = child.wait() {
+            let _ = crate::file::run::write_animus_exit(&animus, child.id(), &status.to_string());
+        }
+    });
+}
+
+// The last lines of a log, to explain a failed launch.
+fn log_tail(path: &str) -> String {
+
+    let log = std::fs::read_to_string(path).unwrap_or_default();
+    let lines: Vec<&str> = log.lines().collect();
+    let start = lines.len().saturating_sub(20);
+
+    lines[start..].join("\n")
+}
@@ -1,5 +1,15 @@
 
 pub(super) mod animate;
+pub(super) mod build;
+pub(super) mod clone;
+pub(super) mod deanimate;
 pub(super) mod list;
 pub(super) mod load;
-
+pub(super) mod network;
+pub(super) mod process;
+pub(super) mod remote;
+pub(super) mod schedule;
+pub(super) mod script;
+pub(super) mod snapshot;
+pub(super) mod supervise;
+pub(super) mod systemd;
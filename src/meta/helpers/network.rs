@@ -0,0 +1,149 @@
+
+use std::path::Path;
+use std::time::{ Duration, SystemTime };
+
+use serde_json::json;
+
+// Counts for one structure of a network.
+struct StructureSummary {
+    name: String,
+    neurons: usize,
+    synapses: usize,
+}
+
+// Copy a network file into ~/.cajal/saved, after checking that it is a network.
+pub(crate) fn import_network(
+    path: &Path,
+    name: Option<String>,
+    overwrite: bool,
+) -> anyhow::Result<()> {
+
+    if ! path.is_file() {
+        return Err(anyhow::anyhow!("'{}' not found", path.display()))
+    }
+
+    let name = match name {
+        Some(name) => name,
+        None => path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+
+    // Network names are used as animus names by default
+    if ! crate::file::animi::valid_animus_name(&name) {
+        return Err(anyhow::anyhow!(
+            "Name '{}' is invalid! (Use a-Z, 0-9, and _) Choose another with --name",
+            name
+        ))
+    }
+
+    crate::file::saved::read_network(path)?;
+
+    if crate::file::saved::network_exists(&name)? && !overwrite {
+        return Err(anyhow::anyhow!(
+            "A network named '{}' is already saved. \
+            Choose another name with --name, or replace it with --overwrite",
+            name
+        ))
+    }
+
+    std::fs::copy(path, crate::file::saved::network_path(&name))?;
+
+    if crate::output::is_json() {
+        crate::output::emit(json!({
+            "command": "import-network",
+            "path": path.display().to_string(),
+            "network": name,
+        }))
+    } else {
+        println!("Imported '{}' as {}", path.display(), name)
+    }
+
+    Ok(())
+}
+
+// Load a network file and describe its structures and tracts,
+// to plan linking and grouping before animating it.
+pub(crate) fn inspect_network(network: &Path) -> anyhow::Result<()> {
+
+    let (network_name, path) = crate::file::saved::resolve_network(network);
+    if ! path.is_file() {
+        return Err(anyhow::anyhow!(
+            "Network '{}' not found. (Tip: Use `list-networks` to view saved networks)",
+            network.display()
+        ))
+    }
+
+    let metadata = std::fs::metadata(&path)?;
+    let modified = metadata.modified()?;
+    let complex = crate::file::saved::read_network(&path)?;
+
+    let structures: Vec<StructureSummary> = complex.structures().iter()
+        .map(|structure| StructureSummary {
+            name: structure.name().to_string(),
+            neurons: structure.neurons().len(),
+            synapses: structure.neurons().iter().map(|n| n.synapses().len()).sum(),
+        })
+        .collect();
+
+    let inputs: Vec<String> = complex.inputs().iter().map(|t| t.name().to_string()).collect();
+    let outputs: Vec<String> = complex.outputs().iter().map(|t| t.name().to_string()).collect();
+
+    let neurons: usize = structures.iter().map(|s| s.neurons).sum();
+    let synapses: usize = structures.iter().map(|s| s.synapses).sum();
+
+    if crate::output::is_json() {
+        let modified = modified.duration_since(std::time::UNIX_EPOCH)?.as_secs();
+        let structures: Vec<_> = structures.iter()
+            .map(|s| json!({ "name": s.name, "neurons": s.neurons, "synapses": s.synapses }))
+            .collect();
+
+        crate::output::emit(json!({
+            "network": network_name,
+            "path": path.display().to_string(),
+            "complex": complex.name(),
+            "size_bytes": metadata.len(),
+            "modified": modified,
+            "structures": structures,
+            "inputs": inputs,
+            "outputs": outputs,
+            "neurons": neurons,
+            "synapses": synapses,
+        }));
+        return Ok(())
+    }
+
+    let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+
+    println!("Network:   {} ({})", network_name, path.display());
+    println!("Complex:   {}", complex.name());
+    println!("Size:      {} bytes", metadata.len());
+    println!("Modified:  {} ago", format_age(age));
+    println!("Neurons:   {}", neurons);
+    println!("Synapses:  {}", synapses);
+
+    println!("\n{:<24} {:>10} {:>10}", "STRUCTURE", "NEURONS", "SYNAPSES");
+    for s in structures.iter() {
+        println!("{:<24} {:>10} {:>10}", s.name, s.neurons, s.synapses)
+    }
+
+    println!("\nInputs:    {}", if inputs.is_empty() { "(none)".to_string() } else { inputs.join(", ") });
+    println!("Outputs:   {}", if outputs.is_empty() { "(none)".to_string() } else { outputs.join(", ") });
+
+    Ok(())
+}
+
+// Describe a length of time in its largest units, e.g., "3d 4h" or "12m 5s".
+pub(super) fn format_age(age: Duration) -> String {
+
+    let secs = age.as_secs();
+    let (days, hours, mins) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m {}s", mins, secs % 60)
+    }
+}
@@ -0,0 +1,221 @@
+
+//! Helper functions for finding and signalling local animusd processes,
+//! by reading `/proc`.
+
+use std::time::{ Duration, Instant };
+
+use animusd_lib::protocol::Action;
+
+use super::load::reap_when_exited;
+
+// Linux reports process times in clock ticks, almost always 100 per second.
+const CLOCK_TICKS: f64 = 100.0;
+
+// A running animusd process.
+pub(crate) struct AnimusProcess {
+    pub(crate) name: String,
+    pub(crate) pid: u32,
+    pub(crate) uptime: Duration,
+    // Average CPU usage over the life of the process, as a percentage.
+    pub(crate) cpu: f64,
+    pub(crate) rss_kb: u64,
+}
+
+// Find every process executing an `animusd-<name>` binary.
+pub(crate) fn running_animi() -> anyhow::Result<Vec<AnimusProcess>> {
+
+    let mut processes = Vec::new();
+
+    for entry in std::fs::read_dir("/proc")?.flatten() {
+        let pid = entry.file_name().to_string_lossy().parse::<u32>();
+        if let Ok(pid) = pid {
+            if let Some(process) = read_process(pid) {
+                processes.push(process)
+            }
+        }
+    }
+
+    processes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(processes)
+}
+
+// PIDs of the processes running a particular animus.
+pub(crate) fn animus_pids(name: &str) -> anyhow::Result<Vec<u32>> {
+    let pids = running_animi()?.into_iter()
+        .filter(|p| p.name == name)
+        .map(|p| p.pid)
+        .collect();
+    Ok(pids)
+}
+
+pub(crate) fn process_alive(pid: u32) -> bool {
+    std::path::Path::new(&format!("/proc/{}", pid)).exists()
+}
+
+// Send a signal (e.g., "TERM" or "KILL") to a process.
+pub(crate) fn signal_process(pid: u32, signal: &str) -> anyhow::Result<()> {
+
+    let mut cmd = std::process::Command::new("kill");
+    cmd.arg(format!("-{}", signal)).arg(pid.to_string());
+
+    let result = cmd.output()?;
+    if ! result.status.success() {
+        return Err(anyhow::anyhow!(
+            "Failed to send SIG{} to {}: {}",
+            signal, pid, String::from_utf8_lossy(&result.stderr).trim()
+        ))
+    }
+
+    Ok(())
+}
+
+// Read process details, if this PID belongs to an animus.
+fn read_process(pid: u32) -> Option<AnimusProcess> {
+
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let program = cmdline.split(|b| *b == 0).next()?;
+    let program = String::from_utf8_lossy(program);
+    let binary = program.rsplit('/').next()?;
+    let name = binary.strip_prefix("animusd-")?.to_string();
+
+    // Fields following the parenthesized command name, starting with the state
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let utime: f64 = fields.get(11)?.parse().ok()?;
+    let stime: f64 = fields.get(12)?.parse().ok()?;
+    let start: f64 = fields.get(19)?.parse().ok()?;
+
+    let system_uptime: f64 = std::fs::read_to_string("/proc/uptime").ok()?
+        .split_whitespace().next()?
+        .parse().ok()?;
+
+    let uptime = (system_uptime - start / CLOCK_TICKS).max(0.0);
+    let cpu = if uptime > 0.0 {
+        (utime + stime) / CLOCK_TICKS / uptime * 100.0
+    } else {
+        0.0
+    };
+
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let rss_kb = status.lines()
+        .find(|l| l.starts_with("VmRSS:"))
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|kb| kb.parse().ok())
+        .unwrap_or(0);
+
+    Some(AnimusProcess {
+        name,
+        pid,
+        uptime: Duration::from_secs_f64(uptime),
+        cpu,
+        rss_kb
+    })
+}
+
+impl crate::Brainstorm {
+
+    // Print the animusd processes running on this device.
+    pub(crate) fn list_processes(&self) -> anyhow::Result<()> {
+
+        let processes = running_animi()?;
+
+        if crate::output::is_json() {
+            let list: Vec<_> = processes.iter()
+                .map(|p| serde_json::json!({
+                    "name": p.name,
+                    "pid": p.pid,
+                    "uptime_secs": p.uptime.as_secs(),
+                    "cpu_percent": p.cpu,
+                    "rss_kb": p.rss_kb,
+                }))
+                .collect();
+            crate::output::emit(serde_json::json!({ "processes": list }));
+            return Ok(())
+        }
+
+        println!("{:<24} {:>8} {:>12} {:>6} {:>10}", "NAME", "PID", "UPTIME", "CPU%", "RSS");
+        for p in processes.iter() {
+            let secs = p.uptime.as_secs();
+            let uptime = format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+            let rss = format!("{}M", p.rss_kb / 1024);
+            println!("{:<24} {:>8} {:>12} {:>6.1} {:>10}", p.name, p.pid, uptime, p.cpu, rss)
+        }
+
+        Ok(())
+    }
+
+    // Signal the processes running an animus,
+    // for when it no longer answers `terminate`.
+    pub(crate) fn kill_animus(&self, animus: &str, force: bool) -> anyhow::Result<()> {
+
+        let pids = animus_pids(animus)?;
+        if pids.is_empty() {
+            return Err(anyhow::anyhow!("No running process found for '{}'", animus))
+        }
+
+        let json = crate::output::is_json();
+
+        let signal = if force { "KILL" } else { "TERM" };
+        for pid in pids.iter() {
+            signal_process(*pid, signal)?;
+            if !json { println!("Sent SIG{} to animusd-{} (PID {})", signal, animus, pid) }
+        }
+
+        if crate::file::animi::local_animus_exists(animus)? {
+            crate::file::run::remove_animus_pid(animus)?
+        }
+
+        if json {
+            crate::output::emit(serde_json::json!({
+                "command": "kill",
+                "animus": animus,
+                "signal": format!("SIG{}", signal),
+                "pids": pids,
+            }))
+        }
+
+        Ok(())
+    }
+
+    // Terminate a local animus, wait for it to exit, then launch it again.
+    pub(crate) fn restart_animus(&self, animus: &str) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus))
+        }
+
+        // An intentional exit, so supervisors should not treat it as a crash
+        crate::file::run::remove_animus_pid(animus)?;
+
+        if self.is_active(animus)? {
+            self.request(animus, Action::Terminate)?;
+        }
+
+        let deadline = Instant::now() + self.config.launch_timeout;
+        while animus_pids(animus)?.iter().any(|pid| process_alive(*pid)) {
+            if Instant::now() > deadline {
+                return Err(anyhow::anyhow!(
+                    "'{}' did not exit after terminating. Use `kill {}` to stop it.",
+                    animus, animus
+                ))
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+
+        let child = self.launch_animus(animus)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(serde_json::json!({
+                "command": "restart",
+                "animus": animus,
+                "pid": child.id(),
+            }))
+        } else {
+            println!("Animus '{}' has restarted (PID {})", animus, child.id())
+        }
+
+        reap_when_exited(animus, child);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+
+use std::net::SocketAddr;
+
+use animusd_lib::protocol::{ Action, Outcome };
+use serde_json::json;
+
+
+impl crate::Brainstorm {
+
+    // Record an animus running on another device, after checking that it answers.
+    pub(crate) fn register_remote(
+        &self,
+        animus: &str,
+        addr: SocketAddr,
+        force: bool,
+    ) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::valid_animus_name(animus) {
+            return Err(anyhow::anyhow!("'{}' is an invalid name.", animus))
+        }
+
+        if crate::file::animi::animus_exists(animus)? {
+            return Err(anyhow::anyhow!("Animus '{}' already exists", animus))
+        }
+
+        let answer = self.verify_remote(animus, addr, force)?;
+        crate::file::remote::write_remote_animus(animus, addr)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(remote_json("add-remote", animus, addr, answer))
+        } else {
+            println!("Registered remote animus '{}' at {}", animus, addr)
+        }
+
+        Ok(())
+    }
+
+    // Change the address recorded for a remote animus.
+    pub(crate) fn edit_remote(
+        &self,
+        animus: &str,
+        addr: SocketAddr,
+        force: bool,
+    ) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::remote_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("No remote record for '{}'", animus))
+        }
+
+        let answer = self.verify_remote(animus, addr, force)?;
+        crate::file::remote::write_remote_animus(animus, addr)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(remote_json("edit-remote", animus, addr, answer))
+        } else {
+            println!("Remote animus '{}' is now at {}", animus, addr)
+        }
+
+        Ok(())
+    }
+
+    // Forget a remote animus. (This will not affect the animus itself.)
+    pub(crate) fn remove_remote(&self, animus: &str) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::remote_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("No remote record for '{}'", animus))
+        }
+
+        crate::file::remote::remove_remote_animus(animus)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(json!({
+                "command": "remove-remote",
+                "animus": animus,
+            }))
+        } else {
+            println!("Removed remote animus '{}'", animus)
+        }
+
+        Ok(())
+    }
+
+    // Query the remote address for its version and network name.
+    // Refuses an address that does not answer, unless forced.
+    fn verify_remote(
+        &self,
+        animus: &str,
+        addr: SocketAddr,
+        force: bool,
+    ) -> anyhow::Result<Option<RemoteAnswer>> {
+
+        let timeout = self.config.timeout;
+
+        let answered = match self.request_at(animus, addr, Action::Query, timeout) {
+            Ok(report) => matches!(report.outcome, Outcome::Success),
+            Err(_) => false,
+        };
+
+        if !answered {
+            if force {
+                eprintln!("WARN: '{}' did not answer at {}, registering anyway", animus, addr);
+                return Ok(None)
+            }
+
+            return Err(anyhow::anyhow!(
+                "'{}' did not answer at {}. Use --force to register it anyway.", 
+                animus, addr
+            ))
+        }
+
+        let version = self.request_at(animus, addr, Action::Version, timeout)
+            .map(|report| crate::output::outcome_text(report.outcome))
+            .unwrap_or_else(|_| "?".to_string());
+        let network = self.request_at(animus, addr, Action::Name, timeout)
+            .map(|report| crate::output::outcome_text(report.outcome))
+            .unwrap_or_else(|_| "?".to_string());
+
+        if ! crate::output::is_json() {
+            println!("'{}' answered at {} (version: {}, network: {})", animus, addr, version, network)
+        }
+
+        Ok(Some(RemoteAnswer { version, network }))
+    }
+}
+
+// What a remote animus said about itself when its address was checked.
+struct RemoteAnswer {
+    version: String,
+    network: String,
+}
+
+// Represent a change to a remote record, with the answer if one was received.
+fn remote_json(
+    command: &str,
+    animus: &str,
+    addr: SocketAddr,
+    answer: Option<RemoteAnswer>,
+) -> serde_json::Value {
+    json!({
+        "command": command,
+        "animus": animus,
+        "address": addr.to_string(),
+        "answered": answer.is_some(),
+        "version": answer.as_ref().map(|a| &a.version),
+        "network": answer.as_ref().map(|a| &a.network),
+    })
+}
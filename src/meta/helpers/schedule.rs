@@ -0,0 +1,489 @@
+
+//! Helper functions for running animus commands on cron-like schedules.
+//! Times are matched in UTC.
+
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
+
+use animusd_lib::protocol::Action;
+
+use crate::file::schedule::ScheduleEntry;
+
+// Commands that can be scheduled.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub(crate) enum ScheduleAction {
+    /// Sleep, wait for activity to drain, save, then wake again if it was awake
+    Save,
+    Sleep,
+    Wake,
+}
+
+impl std::fmt::Display for ScheduleAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleAction::Save => write!(f, "save"),
+            ScheduleAction::Sleep => write!(f, "sleep"),
+            ScheduleAction::Wake => write!(f, "wake"),
+        }
+    }
+}
+
+impl std::str::FromStr for ScheduleAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "save" => Ok(ScheduleAction::Save),
+            "sleep" => Ok(ScheduleAction::Sleep),
+            "wake" => Ok(ScheduleAction::Wake),
+            _ => Err(anyhow::anyhow!("Unknown scheduled action '{}'", s)),
+        }
+    }
+}
+
+// The minutes a schedule runs on, parsed from five cron fields.
+// Each field is a bitmask of the values it allows.
+struct Cron {
+    minute: u64,
+    hour: u64,
+    day: u64,
+    month: u64,
+    weekday: u64,
+    // Cron runs when either day field matches, if both are restricted
+    any_day: bool,
+}
+
+impl Cron {
+
+    fn parse(spec: &str) -> anyhow::Result<Self> {
+
+        let fields: Vec<&str> = spec.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(anyhow::anyhow!(
+                "'{}' needs five fields: minute hour day month weekday", spec
+            ))
+        }
+
+        Ok(Cron {
+            minute: parse_field(fields[0], 0, 59)?,
+            hour: parse_field(fields[1], 0, 23)?,
+            day: parse_field(fields[2], 1, 31)?,
+            month: parse_field(fields[3], 1, 12)?,
+            // Sunday is 0, and also 7
+            weekday: {
+                let days = parse_field(fields[4], 0, 7)?;
+                (days | days >> 7) & 0x7f
+            },
+            any_day: fields[2] != "*" && fields[4] != "*",
+        })
+    }
+
+    // Check whether the schedule runs in the minute containing a Unix time.
+    fn matches(&self, time: u64) -> bool {
+
+        let days = time / 86400;
+        let (_, month, day) = civil_date(days);
+        let weekday = (days + 4) % 7; // 1970-01-01 was a Thursday
+        let minute = time / 60 % 60;
+        let hour = time / 3600 % 24;
+
+        let day_matches = if self.any_day {
+            bit(self.day, day) || bit(self.weekday, weekday)
+        } else {
+            bit(self.day, day) && bit(self.weekday, weekday)
+        };
+
+        bit(self.minute, minute) && bit(self.hour, hour)
+            && bit(self.month, month) && day_matches
+    }
+}
+
+fn bit(mask: u64, value: u64) -> bool {
+    mask & (1u64 << value) != 0
+}
+
+// Parse one cron field: `*`, a value, a range `a-b`, a step `*/n` or `a-b/n`,
+// or a comma-separated list of those.
+fn parse_field(field: &str, min: u64, max: u64) -> anyhow::Result<u64> {
+
+    let mut mask = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u64>()?),
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (start.parse()?, end.parse()?)
+        } else {
+            let value = range.parse()?;
+            (value, value)
+        };
+
+        if start < min || end > max || start > end || step == 0 {
+            return Err(anyhow::anyhow!(
+                "'{}' is out of range ({}-{})", part, min, max
+            ))
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1u64 << value
+        }
+    }
+
+    Ok(mask)
+}
+
+// Convert days since the Unix epoch to a (year, month, day) date.
+fn civil_date(days: u64) -> (u64, u64, u64) {
+
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+impl crate::Brainstorm {
+
+    // Add a recurring command to the schedule.
+    pub(crate) fn add_schedule(
+        &self,
+        cron: &str,
+        action: ScheduleAction,
+        target: &str,
+        group: bool,
+    ) -> anyhow::Result<()> {
+
+        Cron::parse(cron)?;
+
+        let target = if group {
+            if ! crate::file::groups::group_exists(target)? {
+                return Err(anyhow::anyhow!("Group '{}' not found", target))
+            }
+            format!("group:{}", target)
+        } else {
+            if ! crate::file::animi::animus_exists(target)? {
+                return Err(anyhow::anyhow!("'{}' is not a registered animus", target))
+            }
+            target.to_string()
+        };
+
+        let mut entries = crate::file::schedule::read_schedule()?;
+        entries.push(ScheduleEntry {
+            cron: cron.split_whitespace().collect::<Vec<_>>().join(" "),
+            action: action.to_string(),
+            target,
+        });
+        crate::file::schedule::write_schedule(&entries)?;
+
+        let entry = entries.last().expect("An entry was pushed above");
+        if crate::output::is_json() {
+            crate::output::emit(serde_json::json!({
+                "command": "schedule",
+                "added": entries.len(),
+                "cron": entry.cron,
+                "action": entry.action,
+                "target": entry.target,
+            }))
+        } else {
+            println!("Scheduled as #{}", entries.len())
+        }
+
+        Ok(())
+    }
+
+    // Remove an entry from the schedule by its number, as shown by `schedule list`.
+    pub(crate) fn remove_schedule(&self, number: usize) -> anyhow::Result<()> {
+
+        let mut entries = crate::file::schedule::read_schedule()?;
+        if number == 0 || number > entries.len() {
+            return Err(anyhow::anyhow!("There is no schedule #{}", number))
+        }
+
+        let entry = entries.remove(number - 1);
+        crate::file::schedule::write_schedule(&entries)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(serde_json::json!({
+                "command": "schedule",
+                "removed": number,
+                "cron": entry.cron,
+                "action": entry.action,
+                "target": entry.target,
+            }))
+        } else {
+            println!("Removed '{}  {}  {}'", entry.cron, entry.action, entry.target)
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn list_schedule(&self) -> anyhow::Result<()> {
+
+        let entries = crate::file::schedule::read_schedule()?;
+
+        if crate::output::is_json() {
+            let list: Vec<_> = entries.iter()
+                .map(|e| serde_json::json!({
+                    "cron": e.cron,
+                    "action": e.action,
+                    "target": e.target,
+                }))
+                .collect();
+            crate::output::emit(serde_json::json!({ "schedule": list }));
+            return Ok(())
+        }
+
+        println!("{:>3} {:<20} {:<6} {}", "#", "WHEN (UTC)", "ACTION", "TARGET");
+        for (number, e) in entries.iter().enumerate() {
+            println!("{:>3} {:<20} {:<6} {}", number + 1, e.cron, e.action, e.target)
+        }
+
+        Ok(())
+    }
+
+    // Run scheduled commands as they come due, until interrupted.
+    // The schedule is read again each minute, so it can be edited while running.
+    // Commands that came due while others were running are run late, once each.
+    pub(crate) fn run_schedule(&self) -> anyhow::Result<()> {
+
+        if ! crate::output::is_json() {
+            println!("Running scheduled commands. Press Ctrl-C to stop.")
+        }
+
+        let mut last_minute = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() / 60;
+
+        loop {
+            // Wait for the start of the next minute
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+            let into_minute = Duration::from_secs(now.as_secs() % 60)
+                + Duration::from_nanos(now.subsec_nanos() as u64);
+            std::thread::sleep(Duration::from_secs(60) - into_minute);
+
+            let minute = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() / 60;
+            if minute <= last_minute { continue }
+
+            let entries = match crate::file::schedule::read_schedule() {
+                Ok(entries) => entries,
+                Err(e) => {
+                    eprintln!("WARN: Could not read the schedule: {}", e);
+                    continue
+                },
+            };
+
+            // Every minute since the last one handled, oldest first
+            let missed = (last_minute + 1)..=minute;
+            last_minute = minute;
+
+            for entry in entries.iter() {
+                let cron = match Cron::parse(&entry.cron) {
+                    Ok(cron) => cron,
+                    Err(e) => {
+                        eprintln!("WARN: Skipping '{}': {}", entry.cron, e);
+                        continue
+                    },
+                };
+
+                let due = missed.clone().find(|m| cron.matches(m * 60));
+                if let Some(due) = due {
+                    if due < minute {
+                        eprintln!(
+                            "WARN: Running '{}  {}  {}' {} minute(s) late",
+                            entry.cron, entry.action, entry.target, minute - due
+                        )
+                    }
+                    self.run_schedule_entry(entry)
+                }
+            }
+        }
+    }
+
+    // Run one scheduled command for each animus it targets, recording the outcomes.
+    fn run_schedule_entry(&self, entry: &ScheduleEntry) {
+
+        let animi = match entry.target.strip_prefix("group:") {
+            Some(group) => crate::file::groups::read_group_members(group),
+            None => Ok(vec![entry.target.clone()]),
+        };
+
+        let animi = match animi {
+            Ok(animi) => animi,
+            Err(e) => {
+                eprintln!("WARN: Could not read targets of '{}': {}", entry.target, e);
+                let _ = crate::file::schedule::record_run(entry, &entry.target, &format!("failed: {}", e));
+                return
+            },
+        };
+
+        for animus in animi.iter().filter(|a| !a.is_empty()) {
+
+            let result = entry.action.parse::<ScheduleAction>()
+                .and_then(|action| self.run_scheduled_action(animus, action));
+
+            let outcome = match result {
+                Ok(()) => "ok".to_string(),
+                Err(e) => {
+                    crate::report_failure();
+                    // Keep each run on one line of the record
+                    format!("failed: {}", e.to_string().replace('\n', " "))
+                },
+            };
+
+            if crate::output::is_json() {
+                crate::output::emit(serde_json::json!({
+                    "command": "schedule",
+                    "action": entry.action,
+                    "animus": animus,
+                    "outcome": outcome,
+                }))
+            } else {
+                println!("{} {}: {}", entry.action, animus, outcome)
+            }
+            if let Err(e) = crate::file::schedule::record_run(entry, animus, &outcome) {
+                eprintln!("WARN: Could not record the run: {}", e)
+            }
+        }
+    }
+
+    fn run_scheduled_action(&self, animus: &str, action: ScheduleAction) -> anyhow::Result<()> {
+        match action {
+            ScheduleAction::Save => {
+                let note = Some("Scheduled save".to_string());
+                self.save_animus(animus, None, note, true)
+            },
+            ScheduleAction::Sleep => self.request_success(animus, Action::Sleep),
+            ScheduleAction::Wake => self.request_success(animus, Action::Wake),
+        }
+    }
+}
+
+// Point the schedule entries for an animus at its new name,
+// or drop them if it no longer exists. Returns the number of entries changed.
+pub(super) fn retarget_schedule(animus: &str, new_name: Option<&str>) -> anyhow::Result<usize> {
+
+    let entries = crate::file::schedule::read_schedule()?;
+    let count = entries.iter().filter(|entry| entry.target == animus).count();
+    if count == 0 { return Ok(0) }
+
+    let entries: Vec<ScheduleEntry> = entries.into_iter()
+        .filter_map(|mut entry| {
+            if entry.target != animus { return Some(entry) }
+            let new_name = new_name?;
+            entry.target = new_name.to_string();
+            Some(entry)
+        })
+        .collect();
+
+    crate::file::schedule::write_schedule(&entries)?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{ civil_date, parse_field, Cron };
+
+    // Unix time of midnight UTC, a number of days after the epoch.
+    fn midnight(days: u64) -> u64 {
+        days * 86400
+    }
+
+    fn values(mask: u64) -> Vec<u64> {
+        (0..64).filter(|v| mask & (1u64 << v) != 0).collect()
+    }
+
+    #[test]
+    fn parses_fields() {
+        let cases: &[(&str, u64, u64, &[u64])] = &[
+            ("*", 0, 6, &[0, 1, 2, 3, 4, 5, 6]),
+            ("*/15", 0, 59, &[0, 15, 30, 45]),
+            ("5", 0, 59, &[5]),
+            ("1-5", 1, 31, &[1, 2, 3, 4, 5]),
+            ("10-20/5", 0, 59, &[10, 15, 20]),
+            ("1,3,5", 1, 12, &[1, 3, 5]),
+            ("0-2,*/20", 0, 59, &[0, 1, 2, 20, 40]),
+        ];
+
+        for (field, min, max, expected) in cases {
+            let mask = parse_field(field, *min, *max).unwrap();
+            assert_eq!(values(mask), expected.to_vec(), "field '{}'", field);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_fields() {
+        for field in ["60", "5-1", "*/0", "0-60", "a", "1-", ""] {
+            assert!(parse_field(field, 0, 59).is_err(), "field '{}'", field);
+        }
+        assert!(parse_field("0", 1, 31).is_err());
+        assert!(Cron::parse("0 0 * *").is_err());
+    }
+
+    #[test]
+    fn weekday_seven_is_sunday() {
+        assert_eq!(Cron::parse("0 0 * * 7").unwrap().weekday, 1);
+        assert_eq!(Cron::parse("0 0 * * 0,7").unwrap().weekday, 1);
+        assert_eq!(Cron::parse("0 0 * * 5-7").unwrap().weekday, 0b110_0001);
+
+        let sundays = Cron::parse("0 0 * * 7").unwrap();
+        assert!(sundays.matches(midnight(20744)));  // 2026-10-18, a Sunday
+        assert!(sundays.matches(midnight(47540)));  // 2100-02-28, a Sunday
+        assert!(!sundays.matches(midnight(20526))); // 2026-03-14, a Saturday
+    }
+
+    #[test]
+    fn matches_day_of_month_or_day_of_week() {
+
+        // The 1st of the month, or any Friday
+        let either = Cron::parse("0 0 1 * 5").unwrap();
+        assert!(either.matches(midnight(20532)));   // 2026-03-20, a Friday
+        assert!(either.matches(midnight(47541)));   // 2100-03-01, a Monday
+        assert!(either.matches(midnight(19783)));   // 2024-03-01, a Friday
+        assert!(!either.matches(midnight(20526)));  // 2026-03-14, a Saturday
+
+        // With one day field unrestricted, only the other applies
+        let first = Cron::parse("0 0 1 * *").unwrap();
+        assert!(first.matches(midnight(47541)));
+        assert!(!first.matches(midnight(20532)));
+
+        let fridays = Cron::parse("0 0 * * 5").unwrap();
+        assert!(fridays.matches(midnight(20532)));
+        assert!(!fridays.matches(midnight(47541)));
+    }
+
+    #[test]
+    fn matches_time_of_day() {
+        let cron = Cron::parse("30 */6 * * *").unwrap();
+        let day = midnight(20744);
+        assert!(cron.matches(day + 30 * 60));
+        assert!(cron.matches(day + 18 * 3600 + 30 * 60 + 59));
+        assert!(!cron.matches(day + 31 * 60));
+        assert!(!cron.matches(day + 3600 + 30 * 60));
+    }
+
+    #[test]
+    fn converts_dates() {
+        let cases = [
+            (0, (1970, 1, 1)),
+            (11016, (2000, 2, 29)),
+            (19782, (2024, 2, 29)),
+            (19783, (2024, 3, 1)),
+            (20744, (2026, 10, 18)),
+            (47540, (2100, 2, 28)),
+            (47541, (2100, 3, 1)),
+        ];
+
+        for (days, date) in cases {
+            assert_eq!(civil_date(days), date, "day {}", days);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+
+use clap::Parser;
+
+use crate::meta::{ MetaCli, MetaCommand };
+use crate::animus::{ AnimusManagerCli, AnimusCommand };
+use crate::group::{ GroupCli, GroupCommand };
+
+
+// The REPL that a script line is read by.
+enum ScriptContext {
+    Meta,
+    Animus(String),
+    Group(String),
+}
+
+impl ScriptContext {
+
+    // Matches the prompt of the equivalent REPL
+    fn prompt(&self) -> &str {
+        match self {
+            ScriptContext::Meta => "brainstorm",
+            ScriptContext::Animus(animus) => animus,
+            ScriptContext::Group(group) => group,
+        }
+    }
+}
+
+impl crate::Brainstorm {
+
+    // Execute a script file, one REPL line per line.
+    // Lines beginning with '#' are comments.
+    // `select`, `group` and `back` switch between REPLs as they would interactively.
+    pub(crate) fn run_script(&self, path: &std::path::Path, stop_on_error: bool) {
+
+        let script = match std::fs::read_to_string(path) {
+            Ok(script) => script,
+            Err(e) => {
+                if crate::output::is_json() {
+                    let e = anyhow::anyhow!("Could not read script '{}': {}", path.display(), e);
+                    crate::output::emit(crate::output::command_error_json("script", &e))
+                } else {
+                    println!("ERROR: Could not read script '{}'", path.display());
+                    eprintln!("{}", e);
+                }
+                return crate::report_failure()
+            }
+        };
+
+        let mut context = ScriptContext::Meta;
+        let mut executed = 0;
+        let mut failed = 0;
+
+        for (number, line) in script.lines().enumerate() {
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue }
+
+            // Transcript
+            transcript(format!("[{}] {}> {}", number + 1, context.prompt(), line));
+            executed += 1;
+
+            let quit = self.execute_script_line(&mut context, line);
+
+            if crate::take_failure() {
+                failed += 1;
+                transcript(format!("[{}] FAILED", number + 1));
+                if stop_on_error {
+                    transcript(format!("Stopping script at line {}", number + 1));
+                    break
+                }
+            }
+
+            if quit { break }
+        }
+
+        transcript(format!("Script finished: {} lines executed, {} failed", executed, failed));
+
+        // Preserve failure for the exit status
+        if failed > 0 { crate::report_failure() }
+    }
+
+    // Parse and execute a single line in the current context.
+    // Returns true if the script should end.
+    fn execute_script_line(&self, context: &mut ScriptContext, line: &str) -> bool {
+
+        let Some(words) = shlex::split(line) else {
+            transcript("Bad syntax".to_string());
+            crate::report_failure();
+            return false
+        };
+
+        let next = match context {
+
+            ScriptContext::Meta => {
+                match MetaCli::try_parse_from(script_args("brainstorm", words)) {
+                    Err(e) => return script_parse_error(e),
+                    Ok(cli) => match cli.command {
+
+                        MetaCommand::Quit | MetaCommand::Exit => return true,
+
+                        MetaCommand::Select { animus, command: None } => {
+                            if ! self.check_selectable(&animus) { return false }
+                            transcript(format!("Selected animus '{}'", animus));
+                            ScriptContext::Animus(animus)
+                        },
+
+                        MetaCommand::Group { name, command: None } => {
+                            transcript(format!("Selected group '{}'", name));
+                            ScriptContext::Group(name)
+                        },
+
+                        command => {
+                            self.execute_meta_command(command);
+                            return false
+                        },
+                    }
+                }
+            },
+
+            ScriptContext::Animus(animus) => {
+                match AnimusManagerCli::try_parse_from(script_args("animus-manager", words)) {
+                    Err(e) => return script_parse_error(e),
+                    Ok(cli) => match cli.command {
+                        AnimusCommand::Back => ScriptContext::Meta,
+                        command => {
+                            self.execute_animus_command(animus, command);
+                            return false
+                        },
+                    }
+                }
+            },
+
+            ScriptContext::Group(group) => {
+                match GroupCli::try_parse_from(script_args("group-manager", words)) {
+                    Err(e) => return script_parse_error(e),
+                    Ok(cli) => match cli.command {
+                        GroupCommand::Back => ScriptContext::Meta,
+                        command => {
+                            self.execute_group_command(group, command);
+                            return false
+                        },
+                    }
+                }
+            },
+        };
+
+        *context = next;
+        false
+    }
+}
+
+// Prepend the binary name expected by clap.
+fn script_args(bin: &str, words: Vec<String>) -> Vec<String> {
+    std::iter::once(bin.to_string()).chain(words).collect()
+}
+
+// Display the progress of a script.
+// In JSON mode this goes to stderr, leaving stdout to the results of its commands.
+fn transcript(line: String) {
+    if crate::output::is_json() {
+        eprintln!("{}", line)
+    } else {
+        println!("{}", line)
+    }
+}
+
+// Display a line that clap could not parse, and count it as a failure.
+fn script_parse_error(e: clap::Error) -> bool {
+    use clap::error::ErrorKind;
+
+    transcript(e.to_string());
+    if ! matches!(e.kind(), ErrorKind::DisplayHelp | ErrorKind::DisplayVersion) {
+        crate::report_failure()
+    }
+    false
+}
@@ -0,0 +1,176 @@
+
+use std::time::{ Duration, SystemTime, UNIX_EPOCH };
+
+use serde_json::json;
+
+use super::network::format_age;
+
+impl crate::Brainstorm {
+
+    // Copy the network of a local animus before it is saved over,
+    // then delete the oldest snapshots beyond the retention limit.
+    // Remote animi keep their networks on another device, so they are skipped.
+    pub(crate) fn snapshot_before_save(
+        &self,
+        animus: &str,
+        label: Option<String>,
+        note: Option<String>,
+    ) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? { return Ok(()) }
+
+        let snapshot = crate::file::snapshot::take_snapshot(animus, label, note)?;
+        if let Some(snapshot) = snapshot {
+            if ! crate::output::is_json() {
+                println!("Saved snapshot #{} of '{}'", snapshot.number, animus)
+            }
+        }
+
+        let retention = self.config.snapshot_retention;
+        if retention > 0 {
+            crate::file::snapshot::prune_snapshots(animus, retention)?;
+        }
+
+        Ok(())
+    }
+
+    // Labels are used to restore a snapshot, so each can only be given once.
+    pub(crate) fn check_label_unused(animus: &str, label: &Option<String>) -> anyhow::Result<()> {
+
+        if let Some(label) = label {
+            if crate::file::snapshot::find_snapshot(animus, label)?.is_some() {
+                return Err(anyhow::anyhow!("'{}' already has a snapshot labeled '{}'", animus, label))
+            }
+        }
+
+        Ok(())
+    }
+
+    // Take a snapshot on request, e.g., to label a network state worth keeping.
+    pub(crate) fn snapshot_animus(
+        &self,
+        animus: &str,
+        label: Option<String>,
+        note: Option<String>,
+    ) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus))
+        }
+
+        Self::check_label_unused(animus, &label)?;
+
+        let Some(snapshot) = crate::file::snapshot::take_snapshot(animus, label, note)? else {
+            return Err(anyhow::anyhow!("'{}' has no network file", animus))
+        };
+
+        if crate::output::is_json() {
+            crate::output::emit(json!({
+                "command": "snapshot",
+                "animus": animus,
+                "number": snapshot.number,
+                "label": snapshot.label,
+            }))
+        } else {
+            println!("Saved snapshot #{} of '{}'", snapshot.number, animus)
+        }
+
+        Ok(())
+    }
+
+    // Print the snapshots of an animus, oldest first.
+    pub(crate) fn list_snapshots(&self, animus: &str) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus))
+        }
+
+        let snapshots = crate::file::snapshot::read_snapshots(animus)?;
+
+        if crate::output::is_json() {
+            let list: Vec<_> = snapshots.iter()
+                .map(|s| json!({
+                    "number": s.number,
+                    "time": s.time,
+                    "size_bytes": s.size,
+                    "label": s.label,
+                    "note": s.note,
+                }))
+                .collect();
+            crate::output::emit(json!({ "animus": animus, "snapshots": list }));
+            return Ok(())
+        }
+
+        if snapshots.is_empty() {
+            println!("'{}' has no snapshots", animus);
+            return Ok(())
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+        println!("{:>6} {:>12} {:>12} {:<16} {}", "NUMBER", "AGE", "SIZE", "LABEL", "NOTE");
+        for s in snapshots.iter() {
+            let age = format_age(Duration::from_secs(now.saturating_sub(s.time)));
+            println!(
+                "{:>6} {:>12} {:>12} {:<16} {}",
+                s.number, age, s.size,
+                s.label.as_deref().unwrap_or("-"),
+                s.note.as_deref().unwrap_or("")
+            )
+        }
+
+        Ok(())
+    }
+
+    // Replace the network of an animus with a snapshot.
+    // The current network is snapshotted first, so the restore can be undone.
+    pub(crate) fn restore_snapshot(&self, animus: &str, key: &str) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus))
+        }
+
+        let Some(snapshot) = crate::file::snapshot::find_snapshot(animus, key)? else {
+            return Err(anyhow::anyhow!(
+                "'{}' has no snapshot '{}'. Use `snapshots {}` to list them.",
+                animus, key, animus
+            ))
+        };
+
+        let Some(network) = crate::file::animi::local_network_path(animus)? else {
+            return Err(anyhow::anyhow!("'{}' has no network file", animus))
+        };
+
+        let active = self.is_active(animus)?;
+        if active && self.is_awake(animus)? {
+            return Err(anyhow::anyhow!(
+                "'{}' is awake. Sleep or terminate it before restoring.", animus
+            ))
+        }
+
+        let note = format!("Before restoring #{}", snapshot.number);
+        crate::file::snapshot::take_snapshot(animus, None, Some(note))?;
+
+        let source = crate::file::snapshot::snapshot_network_path(animus, snapshot.number);
+        std::fs::copy(source, &network)?;
+
+        if crate::output::is_json() {
+            crate::output::emit(json!({
+                "command": "restore",
+                "animus": animus,
+                "number": snapshot.number,
+                "restart_needed": active,
+            }));
+            return Ok(())
+        }
+
+        println!("Restored '{}' to snapshot #{}", animus, snapshot.number);
+
+        // The animus still holds the old network in memory
+        if active {
+            println!("Use `restart {}` to load the restored network", animus)
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+
+use std::collections::HashMap;
+use std::time::{ Duration, Instant, SystemTime, UNIX_EPOCH };
+
+use animusd_lib::protocol::{ Action, Outcome };
+use serde_json::json;
+
+use super::load::reap_when_exited;
+use super::process::animus_pids;
+
+// Longest wait between restart attempts for an animus that keeps crashing.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+// An animus that stays up this long is considered stable again.
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+// What the supervisor knows about one animus.
+#[derive(Default)]
+struct Supervised {
+    // Present once the supervisor has launched the animus itself
+    child: Option<std::process::Child>,
+    was_awake: bool,
+    failures: u32,
+    next_attempt: Option<Instant>,
+    running_since: Option<Instant>,
+}
+
+impl crate::Brainstorm {
+
+    // Watch local animi and relaunch any that crash, until interrupted.
+    // An animus is supervised while it has a PID file from `load`;
+    // `terminate` and `kill` remove the PID file, so those exits are left alone.
+    // Exit codes come from the supervisor's own children, or from the status
+    // recorded in `run/` by the process that loaded the animus, if it is still running.
+    // With `restore`, a network that can't be read is replaced by its newest good snapshot.
+    pub(crate) fn supervise(
+        &self,
+        animi: Vec<String>,
+        interval: Duration,
+        backoff: Duration,
+        restore: bool,
+    ) -> anyhow::Result<()> {
+
+        let mut state: HashMap<String, Supervised> = HashMap::new();
+        if ! crate::output::is_json() {
+            println!("Supervising local animi. Press Ctrl-C to stop.")
+        }
+
+        loop {
+            let names = if animi.is_empty() {
+                crate::file::animi::local_animus_names()?
+            } else {
+                animi.clone()
+            };
+
+            for animus in names.iter() {
+                let supervised = state.entry(animus.clone()).or_default();
+                if let Err(e) = self.supervise_animus(animus, supervised, backoff, restore) {
+                    eprintln!("WARN: Supervising '{}' failed: {}", animus, e)
+                }
+            }
+
+            std::thread::sleep(interval);
+        }
+    }
+
+    // Check one animus and relaunch it if it has crashed.
+    fn supervise_animus(
+        &self,
+        animus: &str,
+        supervised: &mut Supervised,
+        backoff: Duration,
+        restore: bool,
+    ) -> anyhow::Result<()> {
+
+        // Not loaded, or intentionally stopped
+        let Some(pid) = crate::file::run::read_animus_pid(animus)? else {
+            if let Some(child) = supervised.child.take() { reap_when_exited(animus, child) }
+            return Ok(())
+        };
+
+        // Reap our own child to learn its exit code,
+        // or read the one recorded by whoever launched it
+        let exit = match supervised.child.as_mut() {
+            Some(child) => child.try_wait()?.map(|status| status.to_string()),
+            None => crate::file::run::read_animus_exit(animus, pid)?,
+        };
+
+        let alive = exit.is_none() && animus_pids(animus)?.contains(&pid);
+
+        if alive {
+            let running_since = *supervised.running_since.get_or_insert_with(Instant::now);
+            if running_since.elapsed() > STABLE_AFTER { supervised.failures = 0 }
+            supervised.next_attempt = None;
+
+            if let Ok(report) = self.request(animus, Action::Status) {
+                supervised.was_awake = matches!(report.outcome, Outcome::Success);
+            }
+            return Ok(())
+        }
+
+        // Crashed: wait out the backoff before relaunching
+        if let Some(next) = supervised.next_attempt {
+            if Instant::now() < next { return Ok(()) }
+        } else {
+            let exit = exit.unwrap_or_else(|| "exit code unavailable".to_string());
+            show_event(
+                format!("Animus '{}' (PID {}) has crashed: {}", animus, pid, exit),
+                json!({ "animus": animus, "event": "crashed", "pid": pid, "exit": exit }),
+            );
+            record_crash(animus, pid, &exit)?;
+
+            let delay = (backoff * 2u32.saturating_pow(supervised.failures)).min(MAX_BACKOFF);
+            supervised.next_attempt = Some(Instant::now() + delay);
+            supervised.running_since = None;
+            supervised.child = None;
+            return Ok(())
+        }
+
+        supervised.next_attempt = None;
+        supervised.failures += 1;
+
+        // A crash during a save can leave the network unreadable
+        if restore {
+            if let Some(number) = restore_damaged_network(animus)? {
+                show_event(
+                    format!("Restored '{}' to snapshot #{}", animus, number),
+                    json!({ "animus": animus, "event": "restored", "snapshot": number }),
+                );
+                record_event(animus, &format!("restored snapshot #{}", number))?;
+            }
+        }
+
+        // The relaunched animus loads the network as it was last saved
+        match self.launch_animus(animus) {
+            Ok(child) => {
+                show_event(
+                    format!("Animus '{}' was relaunched (PID {})", animus, child.id()),
+                    json!({ "animus": animus, "event": "relaunched", "pid": child.id() }),
+                );
+                supervised.child = Some(child);
+                record_event(animus, "relaunched")?;
+
+                if supervised.was_awake {
+                    self.request(animus, Action::Wake)?;
+                    show_event(
+                        format!("Animus '{}' was woken", animus),
+                        json!({ "animus": animus, "event": "woken" }),
+                    );
+                }
+            },
+            Err(e) => {
+                // Try again after a longer delay
+                let delay = (backoff * 2u32.saturating_pow(supervised.failures)).min(MAX_BACKOFF);
+                supervised.next_attempt = Some(Instant::now() + delay);
+
+                let reason = e.to_string();
+                let reason = reason.lines().next().unwrap_or_default();
+                record_event(animus, &format!("relaunch failed: {}", reason))?;
+
+                // A failed launch removes the PID file, but the animus stays supervised
+                crate::file::run::write_animus_pid(animus, pid)?;
+                return Err(e)
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Display a supervision event as a line of text, or as a JSON object.
+fn show_event(text: String, mut value: serde_json::Value) {
+    if crate::output::is_json() {
+        value["command"] = json!("supervise");
+        crate::output::emit(value)
+    } else {
+        println!("{}", text)
+    }
+}
+
+// Check that the network an animus loads can be read, and if not,
+// put back its newest snapshot that can. Returns the number of the snapshot restored.
+fn restore_damaged_network(animus: &str) -> anyhow::Result<Option<u32>> {
+
+    let Some(network) = crate::file::animi::local_network_path(animus)? else { return Ok(None) };
+    if crate::file::saved::read_network(&network).is_ok() { return Ok(None) }
+
+    for snapshot in crate::file::snapshot::read_snapshots(animus)?.iter().rev() {
+        let path = crate::file::snapshot::snapshot_network_path(animus, snapshot.number);
+        if crate::file::saved::read_network(std::path::Path::new(&path)).is_ok() {
+            std::fs::copy(&path, &network)?;
+            return Ok(Some(snapshot.number))
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "The network of '{}' can't be read, and no snapshot can replace it", animus
+    ))
+}
+
+// Path of the crash history file for an animus.
+fn crash_history_path(animus: &str) -> String {
+    format!("{}/crashes", crate::file::run::animus_run_dir(animus))
+}
+
+fn record_crash(animus: &str, pid: u32, exit: &str) -> anyhow::Result<()> {
+    record_event(animus, &format!("crashed (PID {}): {}", pid, exit))
+}
+
+// Append a timestamped line to the crash history.
+fn record_event(animus: &str, event: &str) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    std::fs::create_dir_all(crate::file::run::animus_run_dir(animus))?;
+    let mut history = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(crash_history_path(animus))?;
+
+    writeln!(history, "{} {}", time, event)?;
+    Ok(())
+}
@@ -0,0 +1,297 @@
+
+//! Helper functions for running animi and groups as systemd user units,
+//! so they come up on boot without opening the REPL.
+
+use crate::file::systemd::{
+    animus_unit_name,
+    group_target_name,
+    group_link_name,
+};
+
+// When systemd should restart an animus after its process exits.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub(crate) enum RestartPolicy {
+    No,
+    OnFailure,
+    Always,
+}
+
+impl std::fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RestartPolicy::No => write!(f, "no"),
+            RestartPolicy::OnFailure => write!(f, "on-failure"),
+            RestartPolicy::Always => write!(f, "always"),
+        }
+    }
+}
+
+// Run `systemctl --user` with the given arguments.
+fn systemctl(args: &[&str]) -> anyhow::Result<()> {
+
+    let mut cmd = std::process::Command::new("systemctl");
+    cmd.arg("--user").args(args);
+
+    let result = cmd.output()?;
+    if ! result.status.success() {
+        return Err(anyhow::anyhow!(
+            "`systemctl --user {}` failed: {}",
+            args.join(" "), String::from_utf8_lossy(&result.stderr).trim()
+        ))
+    }
+
+    Ok(())
+}
+
+// The units to act on for an animus, or for a group.
+fn target_unit(name: &str, group: bool) -> String {
+    if group { group_target_name(name) } else { animus_unit_name(name) }
+}
+
+// Path of the running Brainstorm executable, for units that call back into it.
+fn brainstorm_exe() -> anyhow::Result<String> {
+    Ok(std::env::current_exe()?.display().to_string())
+}
+
+// Contents of the service unit for a local animus.
+fn animus_unit(animus: &str, restart: RestartPolicy, restart_sec: u64) -> String {
+
+    let animus_dir = crate::file::animi::local_animus_path(animus);
+    let bin_path = format!("{}/bin/animusd-{}", animus_dir, animus);
+    let (out_path, err_path) = crate::file::run::animus_log_paths(animus);
+
+    format!(
+"# Generated by Brainstorm. Use `service install {animus}` to regenerate.
+[Unit]
+Description=Animus {animus}
+After=network.target
+
+[Service]
+Type=simple
+WorkingDirectory={animus_dir}
+ExecStart={bin_path}
+Restart={restart}
+RestartSec={restart_sec}
+StandardOutput=append:{out_path}
+StandardError=append:{err_path}
+
+[Install]
+WantedBy=default.target
+")
+}
+
+// Contents of the service unit that waits for every member of a group
+// to answer a Query, then links their tracts.
+fn group_link_unit(
+    group: &str,
+    local: &[String],
+    restart_sec: u64,
+    brainstorm: &str,
+) -> String {
+
+    let members: Vec<String> = local.iter().map(|a| animus_unit_name(a)).collect();
+    let members = members.join(" ");
+    let target = group_target_name(group);
+
+    format!(
+"# Generated by Brainstorm. Use `service install {group} --group` to regenerate.
+[Unit]
+Description=Link tracts for Brainstorm group {group}
+PartOf={target}
+Requires={members}
+After={members}
+
+[Service]
+Type=oneshot
+RemainAfterExit=yes
+ExecStart={brainstorm} --port 0 group {group} query
+ExecStartPost={brainstorm} --port 0 group {group} auto-link
+Restart=on-failure
+RestartSec={restart_sec}
+")
+}
+
+// Contents of the target unit that brings up a group.
+fn group_target_unit(group: &str, local: &[String]) -> String {
+
+    let mut units: Vec<String> = local.iter().map(|a| animus_unit_name(a)).collect();
+    units.push(group_link_name(group));
+    let units = units.join(" ");
+
+    format!(
+"# Generated by Brainstorm. Use `service install {group} --group` to regenerate.
+[Unit]
+Description=Brainstorm group {group}
+Wants={units}
+After={units}
+
+[Install]
+WantedBy=default.target
+")
+}
+
+impl crate::Brainstorm {
+
+    // Write the service unit for a local animus.
+    pub(crate) fn install_animus_unit(
+        &self,
+        animus: &str,
+        restart: RestartPolicy,
+        restart_sec: u64,
+    ) -> anyhow::Result<()> {
+
+        if ! crate::file::animi::local_animus_exists(animus)? {
+            return Err(anyhow::anyhow!("'{}' is not a local animus", animus))
+        }
+
+        // systemd appends to the logs but will not create their directory
+        std::fs::create_dir_all(crate::file::run::animus_log_dir(animus))?;
+
+        let unit = animus_unit_name(animus);
+        crate::file::systemd::write_unit(&unit, &animus_unit(animus, restart, restart_sec))?;
+        systemctl(&["daemon-reload"])?;
+
+        report_unit("Wrote", &crate::file::systemd::unit_path(&unit));
+        Ok(())
+    }
+
+    // Write the target unit for a group, along with the service units
+    // of its local members and the unit that links them.
+    // Remote members must be brought up on their own devices.
+    pub(crate) fn install_group_units(
+        &self,
+        group: &str,
+        restart: RestartPolicy,
+        restart_sec: u64,
+    ) -> anyhow::Result<()> {
+
+        if ! crate::file::groups::group_exists(group)? {
+            return Err(anyhow::anyhow!("Group '{}' not found", group))
+        }
+
+        if ! crate::file::systemd::valid_unit_name(group) {
+            return Err(anyhow::anyhow!(
+                "Group name '{}' can't be used in a unit name (Use a-Z, 0-9, _ and -)",
+                group
+            ))
+        }
+
+        let mut local = Vec::new();
+        for animus in crate::file::groups::read_group_members(group)? {
+            if animus.is_empty() { continue }
+
+            if crate::file::animi::local_animus_exists(&animus)? {
+                self.install_animus_unit(&animus, restart, restart_sec)?;
+                local.push(animus)
+            } else {
+                eprintln!("WARN: '{}' is not a local animus and will not be started", animus)
+            }
+        }
+
+        let link = group_link_name(group);
+        let link_unit = group_link_unit(group, &local, restart_sec, &brainstorm_exe()?);
+        crate::file::systemd::write_unit(&link, &link_unit)?;
+
+        let target = group_target_name(group);
+        crate::file::systemd::write_unit(&target, &group_target_unit(group, &local))?;
+        systemctl(&["daemon-reload"])?;
+
+        report_unit("Wrote", &crate::file::systemd::unit_path(&link));
+        report_unit("Wrote", &crate::file::systemd::unit_path(&target));
+        Ok(())
+    }
+
+    // Start the unit for an animus or group on login (or boot, with lingering).
+    pub(crate) fn enable_unit(&self, name: &str, group: bool, now: bool) -> anyhow::Result<()> {
+
+        let unit = target_unit(name, group);
+        if ! crate::file::systemd::unit_exists(&unit) {
+            return Err(anyhow::anyhow!("'{}' not found. Use `service install` first", unit))
+        }
+
+        if now {
+            systemctl(&["enable", "--now", unit.as_str()])?
+        } else {
+            systemctl(&["enable", unit.as_str()])?
+        }
+
+        report_unit("Enabled", &unit);
+        Ok(())
+    }
+
+    pub(crate) fn disable_unit(&self, name: &str, group: bool, now: bool) -> anyhow::Result<()> {
+
+        let unit = target_unit(name, group);
+        if now {
+            systemctl(&["disable", "--now", unit.as_str()])?
+        } else {
+            systemctl(&["disable", unit.as_str()])?
+        }
+
+        report_unit("Disabled", &unit);
+        Ok(())
+    }
+
+    // Stop and disable the units for an animus or group, then delete them.
+    // Removing a group leaves the units of its members in place.
+    pub(crate) fn remove_units(&self, name: &str, group: bool) -> anyhow::Result<()> {
+
+        let units = if group {
+            vec![group_target_name(name), group_link_name(name)]
+        } else {
+            vec![animus_unit_name(name)]
+        };
+
+        for unit in units.iter() {
+            if ! crate::file::systemd::unit_exists(unit) { continue }
+
+            systemctl(&["disable", "--now", unit.as_str()])?;
+            crate::file::systemd::remove_unit(unit)?;
+            report_unit("Removed", unit)
+        }
+
+        systemctl(&["daemon-reload"])?;
+        Ok(())
+    }
+}
+
+// Find the groups whose units still start the unit of this animus,
+// e.g., after it was renamed or deanimated, and warn that they need regenerating.
+// They are left in place, since the restart settings they were installed with aren't kept.
+pub(super) fn warn_stale_group_units(animus: &str) -> anyhow::Result<Vec<String>> {
+
+    let unit = animus_unit_name(animus);
+    let mut stale = Vec::new();
+
+    for group in crate::file::groups::group_names()? {
+        let refers = [group_target_name(&group), group_link_name(&group)].iter()
+            .filter_map(|name| std::fs::read_to_string(crate::file::systemd::unit_path(name)).ok())
+            .any(|contents| {
+                contents.split(|c: char| c.is_whitespace() || c == '=').any(|word| word == unit)
+            });
+
+        if refers {
+            eprintln!(
+                "WARN: The units of group '{}' still start {}. \
+                Use `service install {} --group` to regenerate them",
+                group, unit, group
+            );
+            stale.push(group)
+        }
+    }
+
+    Ok(stale)
+}
+
+// Display a change to a unit, as text or as JSON.
+fn report_unit(change: &str, unit: &str) {
+    if crate::output::is_json() {
+        crate::output::emit(serde_json::json!({
+            "command": "service",
+            "change": change.to_lowercase(),
+            "unit": unit,
+        }))
+    } else {
+        println!("{} {}", change, unit)
+    }
+}
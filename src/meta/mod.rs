@@ -3,3 +3,5 @@ mod repl;
 mod error;
 mod helpers;
 
+pub(crate) use repl::{ MetaCli, MetaCommand };
+
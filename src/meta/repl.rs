@@ -9,7 +9,11 @@ use clap_repl::{
 };
 
 use crate::file;
-use super::helpers::list;
+use super::helpers::{ list, network };
+use super::helpers::animate::NamingArgs;
+use super::helpers::build::BuildArgs;
+use super::helpers::schedule::ScheduleAction;
+use super::helpers::systemd::RestartPolicy;
 
 
 #[derive(Parser)]
@@ -17,20 +21,31 @@ use super::helpers::list;
     name = "brainstorm",
     about = "REPL for managing Animus services and networks",
 )]
-struct MetaCli {
+pub(crate) struct MetaCli {
     #[command(subcommand)]
-    command: MetaCommand,
+    pub(crate) command: MetaCommand,
 }
 
 #[derive(Subcommand, Debug)]
-enum MetaCommand {
+pub(crate) enum MetaCommand {
 
     /// Run setup if this is your first time using Brainstorm
     /// and you have not already run `brainstorm --setup`.
     Setup,
 
-    /// List all Animi that are currently active on this device.
-    ListActive,
+    /// List all Animi that respond to a Query within the collection window.
+    ListActive {
+        #[arg(long, default_value_t = 1000, help = 
+            "Time to wait for responses, in milliseconds."
+        )]
+        window: u64,
+
+        #[arg(long, action, help = 
+            "Also broadcast a nameless Query to discover unregistered Animi. \
+            Requires a bind address on a network interface, such as --bind 0.0.0.0"
+        )]
+        broadcast: bool,
+    },
 
     /// List all Animi that have data saved in ~/.cajal/animi/
     ListAll,
@@ -38,14 +53,159 @@ enum MetaCommand {
     /// List all `.nn` networks found in ~/.cajal/saved/
     ListNetworks,
 
+    /// Describe the structures and tracts of a network without animating it.
+    InspectNetwork {
+        #[arg( help = 
+            "Provide the name of a network in ~/.cajal/saved, \
+            or the path to a `.nn` file elsewhere."
+        )]
+        network: std::path::PathBuf,
+    },
+
+    /// Copy a `.nn` network file into ~/.cajal/saved
+    ImportNetwork {
+        #[arg( help = 
+            "Provide the path to the network file."
+        )]
+        path: std::path::PathBuf,
+
+        #[arg(long, help = 
+            "Save the network under this name, instead of the file name."
+        )]
+        name: Option<String>,
+
+        #[arg(long, action, help = 
+            "Replace a saved network that has the same name."
+        )]
+        overwrite: bool,
+    },
+
     /// Create a new Animus for a network.
     Animate {
         #[arg( help = 
             "Provide the name of the `.nn` network to be animated. \
             Brainstorm will search for the file in ~/.cajal/saved. \n\
-            Use `list-networks` to view saved network names."
+            Use `list-networks` to view saved network names, \
+            or give the path to a `.nn` file elsewhere."
+        )]
+        network: std::path::PathBuf,
+
+        #[command(flatten)]
+        naming: NamingArgs,
+
+        #[command(flatten)]
+        build: BuildArgs,
+    },
+
+    /// Delete a local Animus, removing it from every group.
+    /// The Animus must not be running.
+    Deanimate {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+
+        #[arg(long, action, help = 
+            "Keep a tarball of the Animus directory (config, logs and network) \
+            in ~/.cajal/brainstorm/archive/"
+        )]
+        archive: bool,
+
+        #[arg(long, short, action, help = 
+            "Delete without asking for confirmation."
+        )]
+        yes: bool,
+    },
+
+    /// Copy a local Animus under a new name, with its config and network.
+    /// Use this to run a variant experiment on a trained network.
+    Clone {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+
+        #[arg( help = 
+            "Provide a name for the copy. (Use a-Z, 0-9, and _)"
+        )]
+        new_name: String,
+    },
+
+    /// Rename a local Animus and update the groups it belongs to.
+    /// The Animus must not be running.
+    Rename {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+
+        #[arg( help = 
+            "Provide the new name. (Use a-Z, 0-9, and _)"
+        )]
+        new_name: String,
+    },
+
+    /// Copy the network of a local Animus into a new snapshot.
+    /// Snapshots are also taken before each `save`.
+    Snapshot {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+
+        #[arg(long, help = 
+            "Label the snapshot, to restore it by name and keep it from being pruned."
+        )]
+        label: Option<String>,
+
+        #[arg(long, help = 
+            "Attach a note to the snapshot."
+        )]
+        note: Option<String>,
+    },
+
+    /// List the network snapshots of a local Animus.
+    Snapshots {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+    },
+
+    /// Replace the network of a local Animus with a snapshot.
+    /// The Animus must be asleep or terminated.
+    Restore {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+
+        #[arg( help = 
+            "Provide the number or label of the snapshot, as shown by `snapshots`."
         )]
-        network: std::path::PathBuf
+        snapshot: String,
+    },
+
+    /// Build the animusd executable for an existing Animus again.
+    /// The Animus must not be active.
+    Rebuild {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String
+    },
+
+    /// Rebuild an Animus with another version, source or features of animusd,
+    /// keeping its config and network. Installs the latest release by default.
+    /// The Animus must not be active.
+    Upgrade {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+
+        #[command(flatten)]
+        build: BuildArgs,
     },
 
     /// Load and activate an Animus that is saved on this device.
@@ -58,24 +218,101 @@ enum MetaCommand {
         animus: String
     },
 
+    /// List the animusd processes running on this device,
+    /// with PID, uptime, CPU and memory usage.
+    Ps,
+
+    /// Stop the process of a local Animus that no longer answers `terminate`.
+    Kill {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+
+        #[arg(long, action, help = 
+            "Send SIGKILL instead of SIGTERM."
+        )]
+        force: bool,
+    },
+
+    /// Terminate a local Animus and load it again.
+    Restart {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem."
+        )]
+        animus: String,
+    },
+
+    /// Watch local Animi and relaunch any that crash, until interrupted.
+    /// Animi that were awake are woken again after relaunching.
+    /// Crashes are recorded in ~/.cajal/animi/local/<animus>/run/crashes
+    /// Exit codes are recorded by the process that launched each Animus,
+    /// so one loaded by a one-shot `brainstorm load` has none to report.
+    Supervise {
+        #[arg( help = 
+            "Provide the names of the Animi to supervise. \
+            All local Animi that have been loaded are supervised by default."
+        )]
+        animi: Vec<String>,
+
+        #[arg(long, default_value_t = 2000, help = 
+            "Time between checks, in milliseconds."
+        )]
+        interval: u64,
+
+        #[arg(long, default_value_t = 1000, help = 
+            "Delay before relaunching a crashed Animus, in milliseconds. \
+            Doubled for each crash in a row."
+        )]
+        backoff: u64,
+
+        #[arg(long, action, help = 
+            "Before relaunching, check that the saved network can be read, \
+            and restore the newest snapshot that can if it is damaged."
+        )]
+        restore: bool,
+    },
+
+    /// Save, sleep or wake Animi on cron-like schedules.
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+    },
+
+    /// Manage systemd user units that bring up Animi and groups on login.
+    /// (Use `loginctl enable-linger` to bring them up on boot instead.)
+    Service {
+        #[command(subcommand)]
+        command: ServiceCommand,
+    },
+
     /// Select an active Animus to manage.
+    /// Follow with an Animus command to execute it once instead.
+    #[command(alias = "animus")]
     Select {
         #[arg( help = 
             "Provide the name of the Animus as it appears in the filesystem. \
             The Animus must currently be active. \n\
             View active Animi using the `list-active` command."
         )]
-        animus: String
+        animus: String,
+
+        #[command(subcommand)]
+        command: Option<crate::animus::AnimusCommand>,
     },
 
     /// Select a group of animi to manage.
+    /// Follow with a group command to execute it once instead.
     Group {
         #[arg( help = 
             "Provide the name of the group as it appears in the filesystem. \
             To create a new group, enter the desired name,
             then submit Y for the next prompt."
         )]
-        name: String
+        name: String,
+
+        #[command(subcommand)]
+        command: Option<crate::group::GroupCommand>,
     },
 
     /// Register a animus running on another device, 
@@ -89,15 +326,178 @@ enum MetaCommand {
         )]
         animus: String,
         #[arg( help = 
-            "Provide the IP address of the other device -- e.g., 1.2.3.4"
+            "Provide the IP address of the other device -- e.g., 1.2.3.4 or ::1"
+        )]
+        ip: std::net::IpAddr,
+
+        #[arg(long, help = 
+            "Provide the port the Animus listens on, if it is not the default."
+        )]
+        port: Option<u16>,
+
+        #[arg(long, action, help = 
+            "Register the Animus even if it does not answer a Query."
+        )]
+        force: bool,
+    },
+
+    /// Change the address of a remote Animus.
+    EditRemote {
+        #[arg( help = 
+            "Provide the name of the remote Animus, as registered with `add-remote`."
+        )]
+        animus: String,
+        #[arg( help = 
+            "Provide the new IP address of the other device -- e.g., 1.2.3.4 or ::1"
         )]
         ip: std::net::IpAddr,
+
+        #[arg(long, help = 
+            "Provide the port the Animus listens on, if it is not the default."
+        )]
+        port: Option<u16>,
+
+        #[arg(long, action, help = 
+            "Change the address even if the Animus does not answer a Query."
+        )]
+        force: bool,
+    },
+
+    /// Stop tracking a remote Animus. (This will not affect the Animus itself.)
+    RemoveRemote {
+        #[arg( help = 
+            "Provide the name of the remote Animus, as registered with `add-remote`."
+        )]
+        animus: String,
+    },
+
+    /// Switch between plain text and JSON output.
+    Output {
+        #[arg(value_enum, help = 
+            "Use `json` to display results as one JSON object per line."
+        )]
+        format: crate::output::OutputFormat,
     },
 
     /// Exit Brainstorm. (This will not affect any active Animi.)
     Quit, Exit,
 }
 
+#[derive(Subcommand, Debug)]
+pub(crate) enum ScheduleCommand {
+
+    /// Schedule a command for an Animus, or for every member of a group.
+    /// Schedules are kept in ~/.cajal/brainstorm/schedule.cfg
+    Add {
+        #[arg( help = 
+            "Provide five cron fields in UTC, as one argument: \
+            minute hour day month weekday -- e.g., \"0 */6 * * *\" for every 6 hours."
+        )]
+        cron: String,
+
+        #[arg(value_enum, help = 
+            "Provide the command to run. A scheduled save puts an awake Animus to sleep \
+            and waits for it to go quiet before saving, then wakes it again."
+        )]
+        action: ScheduleAction,
+
+        #[arg( help = 
+            "Provide the name of the Animus, or of the group if `--group` is given."
+        )]
+        target: String,
+
+        #[arg(long, action, help = "Run the command for every member of a group.")]
+        group: bool,
+    },
+
+    /// Remove a scheduled command.
+    Remove {
+        #[arg( help = 
+            "Provide the number of the schedule, as shown by `schedule list`."
+        )]
+        number: usize,
+    },
+
+    /// List the scheduled commands.
+    List,
+
+    /// Run scheduled commands as they come due, until interrupted.
+    /// Outcomes are recorded in ~/.cajal/brainstorm/schedule.log
+    /// Run `brainstorm schedule run` as a daemon; it receives reports on a free port,
+    /// so it can run beside `supervise` and the REPL.
+    Run,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ServiceCommand {
+
+    /// Write a unit for a local Animus, or a target for a group
+    /// that starts its local members and then links their tracts.
+    /// Units are written to ~/.config/systemd/user/
+    Install {
+        #[arg( help = 
+            "Provide the name of the Animus as it appears in the filesystem, \
+            or the name of the group if `--group` is given."
+        )]
+        name: String,
+
+        #[arg(long, action, help = 
+            "Install units for a group instead of a single Animus."
+        )]
+        group: bool,
+
+        #[arg(long, value_enum, default_value_t = RestartPolicy::OnFailure, help = 
+            "When systemd should restart an Animus after it exits."
+        )]
+        restart: RestartPolicy,
+
+        #[arg(long, default_value_t = 5, help = 
+            "Time to wait before restarting, in seconds."
+        )]
+        restart_sec: u64,
+    },
+
+    /// Start an installed unit on login.
+    Enable {
+        #[arg( help = 
+            "Provide the name of the Animus, or of the group if `--group` is given."
+        )]
+        name: String,
+
+        #[arg(long, action, help = "Enable the group target.")]
+        group: bool,
+
+        #[arg(long, action, help = "Also start the unit now.")]
+        now: bool,
+    },
+
+    /// Stop starting a unit on login.
+    Disable {
+        #[arg( help = 
+            "Provide the name of the Animus, or of the group if `--group` is given."
+        )]
+        name: String,
+
+        #[arg(long, action, help = "Disable the group target.")]
+        group: bool,
+
+        #[arg(long, action, help = "Also stop the unit now.")]
+        now: bool,
+    },
+
+    /// Stop, disable and delete the units for an Animus or group.
+    /// Removing a group does not remove the units of its members.
+    Remove {
+        #[arg( help = 
+            "Provide the name of the Animus, or of the group if `--group` is given."
+        )]
+        name: String,
+
+        #[arg(long, action, help = "Remove the group units.")]
+        group: bool,
+    },
+}
+
 impl crate::Brainstorm {
 
     pub(crate) fn meta_manager(&self) {
@@ -125,47 +525,145 @@ impl crate::Brainstorm {
     }
 
     fn execute_meta_commands(&self, repl: ClapEditor<MetaCli>) {
-        repl.repl(|cli: MetaCli| {
-            match cli.command {
-
-                MetaCommand::Quit | MetaCommand::Exit => {
-                    println!("Goodbye!");
-                    std::process::exit(0);
-                },
-
-                MetaCommand::ListActive => Self::list_active(),
-                MetaCommand::ListAll => Self::list_all(),
-                MetaCommand::ListNetworks => Self::list_networks(),
-
-                MetaCommand::Animate { network } => self.animate(network),
-                MetaCommand::Load { animus } => self.load(&animus),
-                MetaCommand::Select { animus } => self.select(&animus),
-                MetaCommand::Group { name } => self.group_manager(&name),
-
-                MetaCommand::AddRemote { animus, ip } => Self::add_remote(&animus, ip),
-
-                MetaCommand::Setup => {
-                    if ! file::setup::setup_ok() {
-                        if let Err(e) = file::setup::directory_setup() {
-                            println!("ERROR: Directory setup failed");
-                            eprintln!("{}", e)
-                        }
+        repl.repl(|cli: MetaCli| self.execute_meta_command(cli.command));
+    }
+
+    // Execute a single top-level command, from the REPL or the command line.
+    pub(crate) fn execute_meta_command(&self, command: MetaCommand) {
+        match command {
+
+            MetaCommand::Quit | MetaCommand::Exit => {
+                println!("Goodbye!");
+                std::process::exit(0);
+            },
+
+            MetaCommand::ListActive { window, broadcast } => {
+                self.list_active(window, broadcast)
+            },
+            MetaCommand::ListAll => self.list_all(),
+            MetaCommand::ListNetworks => Self::list_networks(),
+            MetaCommand::InspectNetwork { network } => {
+                if let Err(e) = network::inspect_network(&network) {
+                    Self::meta_command_error("inspect-network", e)
+                }
+            },
+            MetaCommand::ImportNetwork { path, name, overwrite } => {
+                if let Err(e) = network::import_network(&path, name, overwrite) {
+                    Self::meta_command_error("import-network", e)
+                }
+            },
+
+            MetaCommand::Animate { network, naming, build } => {
+                self.animate(network, naming, build)
+            },
+            MetaCommand::Deanimate { animus, archive, yes } => {
+                if let Err(e) = self.deanimate_animus(&animus, archive, yes) {
+                    Self::meta_command_error("deanimate", e)
+                }
+            },
+            MetaCommand::Clone { animus, new_name } => {
+                if let Err(e) = self.clone_animus(&animus, &new_name) {
+                    Self::meta_command_error("clone", e)
+                }
+            },
+            MetaCommand::Rename { animus, new_name } => {
+                if let Err(e) = self.rename_local_animus(&animus, &new_name) {
+                    Self::meta_command_error("rename", e)
+                }
+            },
+            MetaCommand::Snapshot { animus, label, note } => {
+                if let Err(e) = self.snapshot_animus(&animus, label, note) {
+                    Self::meta_command_error("snapshot", e)
+                }
+            },
+            MetaCommand::Snapshots { animus } => {
+                if let Err(e) = self.list_snapshots(&animus) {
+                    Self::meta_command_error("snapshots", e)
+                }
+            },
+            MetaCommand::Restore { animus, snapshot } => {
+                if let Err(e) = self.restore_snapshot(&animus, &snapshot) {
+                    Self::meta_command_error("restore", e)
+                }
+            },
+            MetaCommand::Rebuild { animus } => {
+                if let Err(e) = self.rebuild_animus(&animus) {
+                    Self::meta_command_error("rebuild", e)
+                }
+            },
+            MetaCommand::Upgrade { animus, build } => {
+                if let Err(e) = self.upgrade_animus(&animus, build) {
+                    Self::meta_command_error("upgrade", e)
+                }
+            },
+            MetaCommand::Load { animus } => self.load(&animus),
+            MetaCommand::Ps => {
+                if let Err(e) = self.list_processes() {
+                    Self::meta_command_error("ps", e)
+                }
+            },
+            MetaCommand::Kill { animus, force } => {
+                if let Err(e) = self.kill_animus(&animus, force) {
+                    Self::meta_command_error("kill", e)
+                }
+            },
+            MetaCommand::Restart { animus } => {
+                if let Err(e) = self.restart_animus(&animus) {
+                    Self::meta_command_error("restart", e)
+                }
+            },
+            MetaCommand::Supervise { animi, interval, backoff, restore } => {
+                let interval = std::time::Duration::from_millis(interval);
+                let backoff = std::time::Duration::from_millis(backoff);
+                if let Err(e) = self.supervise(animi, interval, backoff, restore) {
+                    Self::meta_command_error("supervise", e)
+                }
+            },
+            MetaCommand::Schedule { command } => self.schedule(command),
+            MetaCommand::Service { command } => self.service(command),
+            MetaCommand::Select { animus, command } => self.select(&animus, command),
+            MetaCommand::Group { name, command } => match command {
+                Some(command) => self.execute_group_command(&name, command),
+                None => self.group_manager(&name),
+            },
+
+            MetaCommand::AddRemote { animus, ip, port, force } => {
+                self.add_remote(&animus, ip, port, force)
+            },
+            MetaCommand::EditRemote { animus, ip, port, force } => {
+                self.edit_remote_addr(&animus, ip, port, force)
+            },
+            MetaCommand::RemoveRemote { animus } => {
+                if let Err(e) = self.remove_remote(&animus) {
+                    Self::meta_command_error("remove-remote", e)
+                }
+            },
+
+            MetaCommand::Output { format } => crate::output::set_format(format),
+
+            MetaCommand::Setup => {
+                if ! file::setup::setup_ok() {
+                    if let Err(e) = file::setup::directory_setup() {
+                        println!("ERROR: Directory setup failed");
+                        eprintln!("{}", e);
+                        crate::report_failure()
                     }
-                },
-            }
-        });
+                }
+            },
+        }
     }
 
     // List all animi that are listening for commands
-    fn list_active() {
-        if let Err(e) = list::active_animi() {
+    fn list_active(&self, window: u64, broadcast: bool) {
+        let window = std::time::Duration::from_millis(window);
+        if let Err(e) = self.active_animi(window, broadcast) {
             Self::meta_command_error("list-active", e)
         }
     }
 
     // List all animi recorded in the animus directory
-    fn list_all() {
-        if let Err(e) = list::all_animi() {
+    fn list_all(&self) {
+        if let Err(e) = list::all_animi(self.config.animus_port) {
             Self::meta_command_error("list-all", e)
         }
     }
@@ -178,9 +676,11 @@ impl crate::Brainstorm {
     }
 
     // Configure and build a new animus for a network file
-    fn animate(&self, network: std::path::PathBuf) {
-        let network_filename = network.display().to_string();
-        if let Err(e) = self.animate_network(&network_filename) {
+    fn animate(&self, network: std::path::PathBuf, naming: NamingArgs, build: BuildArgs) {
+        let result = build.apply(file::service::BuildOptions::default())
+            .and_then(|options| self.animate_network(&network, naming, options));
+
+        if let Err(e) = result {
             Self::meta_command_error("animate", e)
         }
     }
@@ -192,63 +692,113 @@ impl crate::Brainstorm {
         }
     }
 
-    // Select an active (loaded) animus to issue commands
-    fn select(&self, animus: &str) {
+    // Edit or run the schedule of recurring commands
+    fn schedule(&self, command: ScheduleCommand) {
+
+        let result = match command {
+            ScheduleCommand::Add { cron, action, target, group } => {
+                self.add_schedule(&cron, action, &target, group)
+            },
+            ScheduleCommand::Remove { number } => self.remove_schedule(number),
+            ScheduleCommand::List => self.list_schedule(),
+            ScheduleCommand::Run => self.run_schedule(),
+        };
+
+        if let Err(e) = result {
+            Self::meta_command_error("schedule", e)
+        }
+    }
+
+    // Install and control the systemd units for an animus or group
+    fn service(&self, command: ServiceCommand) {
+
+        let result = match command {
+            ServiceCommand::Install { name, group: true, restart, restart_sec } => {
+                self.install_group_units(&name, restart, restart_sec)
+            },
+            ServiceCommand::Install { name, group: false, restart, restart_sec } => {
+                self.install_animus_unit(&name, restart, restart_sec)
+            },
+            ServiceCommand::Enable { name, group, now } => self.enable_unit(&name, group, now),
+            ServiceCommand::Disable { name, group, now } => self.disable_unit(&name, group, now),
+            ServiceCommand::Remove { name, group } => self.remove_units(&name, group),
+        };
+
+        if let Err(e) = result {
+            Self::meta_command_error("service", e)
+        }
+    }
+
+    // Select an active (loaded) animus to issue commands,
+    // or execute a single command if one is given.
+    fn select(&self, animus: &str, command: Option<crate::animus::AnimusCommand>) {
+
+        if ! self.check_selectable(animus) { return }
+
+        match command {
+            Some(command) => self.execute_animus_command(&animus, command),
+            None => self.animus_manager(&animus),
+        }
+    }
+
+    // Check that an animus is active before it is selected.
+    pub(crate) fn check_selectable(&self, animus: &str) -> bool {
         let is_active = self.is_active(&animus);
 
         if let Err(e) = is_active {
-            return Self::meta_command_error("select", e)
+            Self::meta_command_error("select", e);
+            return false
         } else if !is_active.expect("Checked above") {
 
-            return Self::meta_command_error(
+            Self::meta_command_error(
                 "select", 
                 anyhow::anyhow!("'{}' is not active", &animus)
-            )
+            );
+            return false
         }
         
         let exists = file::animi::animus_exists(&animus);
 
         if let Err(e) = exists {
-            return Self::meta_command_error("select", e)
+            Self::meta_command_error("select", e);
+            return false
         } else if !exists.expect("Checked above") {
             // NOTE Continues with warning, in case of emergency.
             // For example, recovery after accidental erasure or file corruption.
             println!("WARN: '{}' is unregistered", &animus)
         }
 
-        self.animus_manager(&animus)
+        true
     }
 
     // Track an animus that is running on another device
-    fn add_remote(animus: &str, ip: std::net::IpAddr) {
-
-        match crate::file::animi::animus_exists(&animus) {
-            Ok(exists) => {
-                if exists {
-                    return Self::meta_command_error(
-                        "add-remote", 
-                        anyhow::anyhow!("Animus '{}' already exists", &animus)
-                    )
-                } else {
-                    // TODO
-                    // Query remote device at ip:4048
-                    // if active,
-                    if let Err(e) = crate::file::remote::write_remote_animus(&animus, ip) {
-                        return Self::meta_command_error("add-remote", e)
-                    }
-                }
-            },
-            Err(e) => {
-                return Self::meta_command_error("add-remote", e)
-            }
+    fn add_remote(&self, animus: &str, ip: std::net::IpAddr, port: Option<u16>, force: bool) {
+
+        let addr = std::net::SocketAddr::new(ip, port.unwrap_or(self.config.animus_port));
+        if let Err(e) = self.register_remote(animus, addr, force) {
+            Self::meta_command_error("add-remote", e)
+        }
+    }
+
+    // Move a remote animus to a new address
+    fn edit_remote_addr(&self, animus: &str, ip: std::net::IpAddr, port: Option<u16>, force: bool) {
+
+        let addr = std::net::SocketAddr::new(ip, port.unwrap_or(self.config.animus_port));
+        if let Err(e) = self.edit_remote(animus, addr, force) {
+            Self::meta_command_error("edit-remote", e)
         }
     }
 
     // Handle errors
     fn meta_command_error(cmd: &str, e: anyhow::Error) {
         
-        println!("WARN: An error occurred while executing '{}' command", cmd);
-        eprintln!("{}", e);
+        if crate::output::is_json() {
+            crate::output::emit(crate::output::command_error_json(cmd, &e))
+        } else {
+            println!("WARN: An error occurred while executing '{}' command", cmd);
+            eprintln!("{}", e);
+        }
+        crate::report_failure()
     }
 }
 
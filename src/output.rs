@@ -0,0 +1,109 @@
+
+//! Helper functions for formatting command output as text or JSON
+
+use std::sync::atomic::{ AtomicBool, Ordering };
+
+use serde_json::{ json, Value };
+
+use animusd_lib::protocol::{ Action, Report, Outcome };
+use cajal_cx::tract::receiver::ReceiverInfo;
+
+
+/// Format used to display command results.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub(crate) enum OutputFormat {
+    Text,
+    Json,
+}
+
+// Set by `--output json` or the `output` command.
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_format(format: OutputFormat) {
+    JSON_OUTPUT.store(format == OutputFormat::Json, Ordering::Relaxed)
+}
+
+// Should results be emitted as JSON?
+pub(crate) fn is_json() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+// Print one JSON object per line.
+pub(crate) fn emit(value: Value) {
+    println!("{}", value)
+}
+
+// Represent an error that occurred while executing a command.
+pub(crate) fn command_error_json(command: &str, e: &anyhow::Error) -> Value {
+    json!({
+        "command": command,
+        "error": e.to_string(),
+    })
+}
+
+// Represent an error that occurred while communicating with an animus.
+pub(crate) fn animus_error_json(animus: &str, e: &anyhow::Error) -> Value {
+    json!({
+        "animus": animus,
+        "error": e.to_string(),
+    })
+}
+
+// Represent a report, including any data returned by the animus.
+pub(crate) fn report_json(report: &Report) -> Value {
+    json!({
+        "animus": report.name,
+        "action": report.action.to_string(),
+        "outcome": outcome_kind(&report.outcome),
+        "data": return_data(&report.action, &report.outcome),
+    })
+}
+
+// Represent the address of an Input.
+pub(crate) fn receiver_json(info: &ReceiverInfo) -> Value {
+    json!({
+        "tract": info.tract_name,
+        "address": info.address.to_string(),
+    })
+}
+
+// Display a returned string if there is one, otherwise the outcome itself.
+pub(crate) fn outcome_text(outcome: Outcome) -> String {
+    match &outcome {
+        Outcome::Return(msg) => bincode::deserialize::<String>(msg)
+            .unwrap_or_else(|_| outcome.to_string()),
+        _ => outcome.to_string(),
+    }
+}
+
+fn outcome_kind(outcome: &Outcome) -> String {
+    match outcome {
+        Outcome::Success => "success".to_string(),
+        Outcome::Return(..) => "return".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// Decode the data in Outcome::Return according to the action that requested it.
+// Data that cannot be decoded is given as raw bytes.
+fn return_data(action: &Action, outcome: &Outcome) -> Value {
+
+    let Outcome::Return(msg) = outcome else { return Value::Null };
+    use bincode::deserialize as de;
+
+    let decoded = match action {
+        Action::ListStructures | Action::ListInputs | Action::ListOutputs => {
+            de::<Vec<String>>(msg).ok().map(|list| json!(list))
+        },
+        Action::ReportInputs => {
+            de::<Vec<ReceiverInfo>>(msg).ok()
+                .map(|list| Value::Array(list.iter().map(receiver_json).collect()))
+        },
+        Action::InputInfo(..) => {
+            de::<ReceiverInfo>(msg).ok().map(|info| receiver_json(&info))
+        },
+        _ => de::<String>(msg).ok().map(|text| json!(text)),
+    };
+
+    decoded.unwrap_or_else(|| json!({ "raw": msg }))
+}